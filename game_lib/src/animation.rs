@@ -1,86 +1,254 @@
-use std::time::{Duration, Instant};
-use crate::{rect::Rect, bird::Bird};
+use std::time::Duration;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use crate::{rect::{Anchor, Rect}, bird::Bird};
+use crate::clock::{Clock, SystemClock};
+use crate::collision::{first_contact, Aabb, Contact};
+use crate::config::{ConfigError, GameConfig};
+use crate::difficulty::Difficulty;
 use crate::game::GameSession;
-use crate::RECT_INTERVAL;
+use crate::solvability::{can_pass_all, Forecast, Reachable};
+use crate::sprite::{AnimatedSprite, Sprite};
 
-pub struct Animation {
+/// Obstacles drawn for one spawn before an impossible spawn is skipped
+pub const SPAWN_ATTEMPTS: usize = 8;
+
+/// Smallest screen an [`Animation`] lays the world out for
+///
+/// On a smaller screen only part of the world is shown.
+pub const MIN_SCREEN_SIZE: (u16, u16) = (40, 16);
+
+/// Owns the moving parts of a game and advances them frame by frame
+///
+/// Obstacle spawning is timed against a [`Clock`]. By default this is the
+/// system clock; tests and simulations can supply a
+/// [`ManualClock`](crate::clock::ManualClock) to step time exactly.
+///
+/// Obstacles are generated from a seeded RNG, so two animations with the
+/// same seed and the same clock produce identical runs.
+///
+/// Obstacle speed, spawn rate and sizes follow the config's
+/// [`DifficultyCurve`](crate::difficulty::DifficultyCurve) for the session's
+/// current level. A new obstacle is only spawned if the bird, played
+/// perfectly from where it is, can still get past everything on screen; see
+/// [`solvability`](crate::solvability).
+///
+/// Points are scored when the bird's left edge gets past an obstacle's right
+/// edge, following the config's [`ScoreRules`](crate::score::ScoreRules).
+///
+/// A clone keeps a clone of the clock, so with a
+/// [`ManualClock`](crate::clock::ManualClock) both copies share its time.
+#[derive(Clone)]
+pub struct Animation<C: Clock = SystemClock> {
     rectangles: Vec<Rect>,
     bird: Bird,
-    last_spawn_time: Instant,
+    config: GameConfig,
+    clock: C,
+    seed: u64,
+    rng: StdRng,
+    last_spawn_time: Duration,
+    last_update_time: Duration,
     screen_size: (u16, u16),
     game_over: bool,
+    contact: Option<Contact>,
+    spawned: u32,
+    combo: u32,
+    bird_sprite: AnimatedSprite,
+    obstacle_texture: Option<Sprite>,
 }
 
 impl Animation {
-    pub fn new(screen_size: (u16, u16)) -> Self {
-        Self {
+    /// Creates a new animation driven by the system clock
+    ///
+    /// # Panics
+    /// Panics if `config` fails [`GameConfig::validate`].
+    pub fn new(screen_size: (u16, u16), config: GameConfig) -> Self {
+        Self::with_clock(screen_size, config, SystemClock::new())
+    }
+}
+
+impl<C: Clock> Animation<C> {
+    /// Creates a new animation driven by the given clock
+    ///
+    /// # Panics
+    /// Panics if `config` fails [`GameConfig::validate`].
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::{animation::Animation, clock::ManualClock, config::GameConfig, game::GameSession, RECT_INTERVAL};
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let mut session = GameSession::new();
+    /// let mut animation = Animation::with_clock((80, 24), GameConfig::default(), clock.clone());
+    /// session.start();
+    ///
+    /// clock.advance(Duration::from_millis(RECT_INTERVAL));
+    /// animation.update(&mut session);
+    /// assert!(!animation.get_rectangles().is_empty());
+    /// ```
+    pub fn with_clock(screen_size: (u16, u16), config: GameConfig, clock: C) -> Self {
+        Self::try_with_clock(screen_size, config, clock).unwrap_or_else(|err| panic!("invalid game config: {err}"))
+    }
+
+    /// Creates a new animation driven by the given clock, checking `config` first
+    ///
+    /// Use this instead of [`Animation::with_clock`] for configs that come
+    /// from outside the program, such as files.
+    ///
+    /// # Errors
+    /// Returns the first invariant of [`GameConfig::validate`] that `config`
+    /// violates.
+    pub fn try_with_clock(screen_size: (u16, u16), config: GameConfig, clock: C) -> Result<Self, ConfigError> {
+        config.validate()?;
+        let screen_size = fit(screen_size);
+        let last_spawn_time = clock.now();
+        let seed = rand::random();
+        Ok(Self {
             rectangles: Vec::new(),
-            bird: Bird::new(screen_size),
-            last_spawn_time: Instant::now(),
+            bird: Bird::new(screen_size, &config),
+            config,
+            clock,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            last_spawn_time,
+            last_update_time: last_spawn_time,
             screen_size,
             game_over: false,
-        }
+            contact: None,
+            spawned: 0,
+            combo: 0,
+            bird_sprite: Bird::default_sprite(),
+            obstacle_texture: None,
+        })
     }
 
+    /// Advances the world by one step
+    ///
+    /// The world only moves while the session is
+    /// [running](GameSession::is_running). Before that the session's
+    /// countdown is advanced by the time passed on the clock, and obstacle
+    /// spawning waits with it. A crash ends the session's run.
     pub fn update(&mut self, game_session: &mut GameSession) {
+        let now = self.clock.now();
+        let elapsed = now.saturating_sub(self.last_update_time);
+        self.last_update_time = now;
+        // Drawing blends from here to wherever this update moves things
+        self.bird.start_step();
+        for rect in &mut self.rectangles {
+            rect.start_step();
+        }
         if self.game_over {
             return;
         }
+        if !game_session.is_running() {
+            // Time spent waiting does not count towards the next spawn
+            self.last_spawn_time += elapsed;
+            game_session.advance(elapsed);
+            return;
+        }
+        let difficulty = self.config.difficulty.at_level(game_session.get_level());
 
         // Remove rectangles that have moved off screen
-        self.rectangles.retain(|rect| rect.get_x() > 0);
+        self.rectangles.retain(|rect| rect.get_x() > 0.0);
 
         // Move all rectangles to the left
         for rect in &mut self.rectangles {
-            rect.set_x(rect.get_x().saturating_sub(1));
+            rect.set_x(rect.get_x() - difficulty.scroll_speed);
         }
 
         // Update bird position
         self.bird.update();
 
         // Check for collisions
-        if self.check_collision() {
+        if let Some(contact) = self.check_collision() {
+            self.contact = Some(contact);
             self.game_over = true;
+            game_session.end();
             return;
         }
 
-        // Spawn new rectangle if enough time has passed
-        if self.last_spawn_time.elapsed() >= Duration::from_millis(RECT_INTERVAL) {
-            let max_width = 10;
-            // Create new rectangle at the right edge
-            let new_rect = Rect::random(max_width, self.screen_size);
-            
-            self.rectangles.push(new_rect);
-            self.last_spawn_time = Instant::now();
-
-            game_session.increase_score();
+        // Score obstacles the bird has got past
+        self.score_passed(game_session);
 
-            if game_session.get_score() % 5 == 0 {
-                game_session.increase_level();
+        // Spawn new rectangle if enough time has passed
+        if now.saturating_sub(self.last_spawn_time) >= Duration::from_millis(difficulty.rect_interval) {
+            if let Some(mut obstacle) = self.generate(&difficulty, game_session) {
+                self.spawned += 1;
+                for rect in &mut obstacle {
+                    rect.set_obstacle(self.spawned);
+                }
+                self.rectangles.extend(obstacle);
             }
+            self.last_spawn_time = now;
         }
     }
 
-    fn check_collision(&self) -> bool {
-        let bird_right = self.bird.get_rightmost_x();
-        let bird_left = self.bird.get_leftmost_x();
-        let bird_bottom = self.bird.get_bottom_y();
+    /// Draws a new obstacle at the right edge that can be flown past
+    ///
+    /// Candidates that would leave the bird no way through, together with
+    /// the obstacles already on screen, are drawn again. Returns `None` if
+    /// every one of [`SPAWN_ATTEMPTS`] candidates was impossible; the spawn
+    /// is then skipped.
+    fn generate(&mut self, difficulty: &Difficulty, game_session: &GameSession) -> Option<Vec<Rect>> {
+        for _ in 0..SPAWN_ATTEMPTS {
+            // A floor block, a ceiling block or a pipe pair
+            let candidate = match self.rng.gen_range(0..3) {
+                0 => vec![Rect::random(difficulty, self.screen_size, &mut self.rng)],
+                1 => vec![Rect::random_ceiling(difficulty, self.screen_size, &mut self.rng)],
+                _ => Rect::random_pair(difficulty, self.screen_size, &mut self.rng).to_vec(),
+            };
+            let forecast = Forecast::new(
+                self.rectangles.iter().chain(&candidate),
+                &self.bird,
+                self.config.difficulty,
+                game_session,
+            );
+            if can_pass_all(&Reachable::new(&self.bird, &self.config), &self.bird, forecast) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
 
-        for rect in &self.rectangles {
-            let rect_left = rect.get_leftmost_x();
-            let rect_right = rect.get_rightmost_x();
-            let rect_top = rect.get_top_y();
+    /// Scores every obstacle the bird has newly passed
+    ///
+    /// Also tracks how close the bird comes to the obstacles it is flying
+    /// through, for the close-call bonus. Rectangles of one obstacle are
+    /// passed and scored together.
+    fn score_passed(&mut self, game_session: &mut GameSession) {
+        let bird = self.bird.get_bounds();
+        for rect in &mut self.rectangles {
+            let bounds = rect.get_bounds();
+            if bounds.left() < bird.right() && bird.left() < bounds.right() {
+                rect.record_clearance(clearance(&bird, &bounds, rect.get_anchor()));
+            }
+        }
 
-            // Check if bird's right edge collides with rectangle's left edge
-            if bird_right >= rect_left && bird_left <= rect_right && bird_bottom >= rect_top {
-                return true;
+        while let Some(obstacle) = self
+            .rectangles
+            .iter()
+            .find(|rect| !rect.is_passed() && rect.get_bounds().right() <= bird.left())
+            .map(Rect::get_obstacle)
+        {
+            let mut closest = None;
+            for rect in self.rectangles.iter_mut().filter(|rect| rect.get_obstacle() == obstacle) {
+                rect.mark_passed();
+                closest = closest.into_iter().chain(rect.get_clearance()).min();
             }
-            else {
-                continue;
+
+            let pass = self.config.score.score_pass(closest, self.combo);
+            self.combo = pass.combo;
+            game_session.pass_obstacle(pass.points);
+
+            if game_session.get_passed() % 5 == 0 {
+                game_session.increase_level();
             }
         }
+    }
 
-        false
+    fn check_collision(&self) -> Option<Contact> {
+        let bounds = self.rectangles.iter().map(Rect::get_bounds);
+        first_contact(&self.bird, bounds)
     }
 
     pub fn jump(&mut self) {
@@ -101,10 +269,140 @@ impl Animation {
         self.game_over
     }
 
+    /// Returns the number of close calls in a row, 0 after an ordinary pass
+    pub fn get_combo(&self) -> u32 {
+        self.combo
+    }
+
+    /// Returns the collision that ended the run, if any
+    pub fn get_contact(&self) -> Option<Contact> {
+        self.contact
+    }
+
+    /// Changes the bird's look, now and for every following run
+    pub fn set_bird_sprite(&mut self, sprite: AnimatedSprite) {
+        self.bird_sprite = sprite;
+        self.bird = Bird::with_sprite(self.screen_size, &self.config, self.bird_sprite.clone());
+    }
+
+    /// Sets the texture obstacles are drawn with, or `None` for plain blocks
+    pub fn set_obstacle_texture(&mut self, texture: Option<Sprite>) {
+        self.obstacle_texture = texture;
+    }
+
+    /// Returns the texture obstacles are drawn with, if any
+    pub fn get_obstacle_texture(&self) -> Option<&Sprite> {
+        self.obstacle_texture.as_ref()
+    }
+
+    /// Returns the configuration this animation was created with
+    pub fn get_config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Returns the size of the screen the world is laid out for
+    pub fn get_screen_size(&self) -> (u16, u16) {
+        self.screen_size
+    }
+
+    /// Lays the world out for a new screen size, e.g. after the terminal was
+    /// resized
+    ///
+    /// Obstacles stay on their edge of the screen, and their heights and the
+    /// bird's height are scaled with the screen's height. Obstacles keep their
+    /// distance from the bird, so on a narrower screen the ones past the
+    /// right edge scroll in later, and new ones spawn at the new right edge.
+    /// Pipe pairs keep a gap the bird fits through, and if an obstacle grows
+    /// over the bird, the bird is moved out of it.
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::animation::Animation;
+    /// use game_lib::config::GameConfig;
+    ///
+    /// let mut animation = Animation::new((80, 24), GameConfig::default());
+    /// animation.resize((120, 48));
+    /// assert_eq!(animation.get_screen_size(), (120, 48));
+    /// assert_eq!(animation.get_bird().get_position().1, 24.0);
+    /// ```
+    pub fn resize(&mut self, screen_size: (u16, u16)) {
+        let screen_size = fit(screen_size);
+        // The halves of a pipe pair are spawned next to each other
+        let mut i = 0;
+        while i < self.rectangles.len() {
+            let (head, tail) = self.rectangles.split_at_mut(i + 1);
+            let rect = &mut head[i];
+            match tail.first_mut().filter(|next| next.get_obstacle() == rect.get_obstacle()) {
+                Some(bottom) => {
+                    Rect::resize_pair(rect, bottom, screen_size);
+                    i += 2;
+                }
+                None => {
+                    rect.resize(screen_size);
+                    i += 1;
+                }
+            }
+        }
+        self.bird.resize(screen_size);
+        let obstacles: Vec<Aabb> = self.rectangles.iter().map(Rect::get_bounds).collect();
+        self.bird.clear_of(&obstacles);
+        self.screen_size = screen_size;
+    }
+
+    /// Returns the seed of the current run
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restarts obstacle generation from the given seed
+    ///
+    /// Call this before the first update to replay a previous run.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Returns the clock driving this animation
+    pub fn get_clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Drives this animation from `clock` from now on
+    pub(crate) fn set_clock(&mut self, clock: C) {
+        self.clock = clock;
+    }
+
+    /// Starts a new run with a fresh random seed
     pub fn restart(&mut self, game_session: &mut GameSession) {
+        self.restart_with_seed(rand::random(), game_session);
+    }
+
+    /// Starts a new run that replays the obstacles of `seed`
+    pub fn restart_with_seed(&mut self, seed: u64, game_session: &mut GameSession) {
         self.game_over = false;
+        self.contact = None;
+        self.spawned = 0;
+        self.combo = 0;
         self.rectangles.clear();
-        self.last_spawn_time = Instant::now();
+        self.bird = Bird::with_sprite(self.screen_size, &self.config, self.bird_sprite.clone());
+        self.last_spawn_time = self.clock.now();
+        self.last_update_time = self.last_spawn_time;
+        self.set_seed(seed);
         game_session.start();
+        game_session.set_seed(seed);
     }
-}
\ No newline at end of file
+}
+
+/// Free rows between the bird and an obstacle it shares columns with
+fn clearance(bird: &Aabb, obstacle: &Aabb, anchor: Anchor) -> u16 {
+    let rows = match anchor {
+        Anchor::Bottom => obstacle.top() - bird.bottom(),
+        Anchor::Top => bird.top() - obstacle.bottom(),
+    };
+    rows.max(0.0) as u16
+}
+
+/// Grows `screen_size` to at least [`MIN_SCREEN_SIZE`]
+fn fit(screen_size: (u16, u16)) -> (u16, u16) {
+    (screen_size.0.max(MIN_SCREEN_SIZE.0), screen_size.1.max(MIN_SCREEN_SIZE.1))
+}
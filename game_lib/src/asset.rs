@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::render::Color;
+use crate::sprite::{AnimatedSprite, Sprite};
+
+/// Line that starts a new frame of art
+const FRAME_SEPARATOR: &str = "---";
+
+/// Reads a sprite asset file
+///
+/// See [`parse_sprite`] for the format.
+pub fn load_sprite(path: &Path) -> Result<AnimatedSprite, AssetError> {
+    let text = fs::read_to_string(path).map_err(AssetError::Io)?;
+    parse_sprite(&text)
+}
+
+/// Parses a sprite asset from plain-text art with a color legend
+///
+/// A header of `key = value` lines comes first, then one or more frames,
+/// each introduced by a `---` line. In the header:
+///
+/// - `color = <color>` sets the color of characters not in the legend
+///   (white if not given)
+/// - `<char> = <color>` adds a legend entry; quote the character as `'='`
+///   or `'#'` when it would otherwise be ambiguous
+/// - `frame_ticks = <n>` shows each frame for `n` updates (1 if not given)
+/// - blank lines and lines starting with `#` are ignored
+///
+/// Colors use the names accepted by [`Color`]'s `FromStr` implementation.
+/// In the art, spaces are empty cells and trailing blank lines of a frame
+/// are dropped.
+///
+/// # Example
+/// ```
+/// use game_lib::asset::parse_sprite;
+/// use game_lib::render::Color;
+///
+/// let bird = parse_sprite("\
+/// color = yellow
+/// > = red
+/// ---
+///    |>
+/// |::|
+/// ---
+///    |>
+/// |..|
+/// ").unwrap();
+///
+/// assert_eq!(bird.get_frames().len(), 2);
+/// assert_eq!(bird.frame_at(0).get(4, 0), Some(('>', Color::Red)));
+/// assert_eq!(bird.frame_at(1).get(1, 1), Some(('.', Color::Yellow)));
+/// ```
+pub fn parse_sprite(text: &str) -> Result<AnimatedSprite, AssetError> {
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line));
+    let mut default_color = Color::White;
+    let mut legend = HashMap::new();
+    let mut ticks_per_frame = 1;
+
+    // Header
+    let mut frame_start = None;
+    for (number, line) in lines.by_ref() {
+        let line = line.trim();
+        if line == FRAME_SEPARATOR {
+            frame_start = Some(number);
+            break;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = split_entry(line).ok_or(AssetError::Syntax { line: number })?;
+        match key {
+            Key::Name("color") => default_color = parse_color(value, number)?,
+            Key::Name("frame_ticks") => {
+                ticks_per_frame = match value.parse() {
+                    Ok(ticks) if ticks > 0 => ticks,
+                    _ => return Err(AssetError::FrameTicks { line: number }),
+                };
+            }
+            Key::Char(ch) => {
+                legend.insert(ch, parse_color(value, number)?);
+            }
+            Key::Name(name) => {
+                return Err(AssetError::UnknownKey {
+                    line: number,
+                    key: name.to_string(),
+                })
+            }
+        }
+    }
+
+    // Frames
+    let mut frames = Vec::new();
+    let mut rows: Vec<&str> = Vec::new();
+    let mut start = frame_start.ok_or(AssetError::NoFrames)?;
+    for (number, line) in lines {
+        if line.trim_end() == FRAME_SEPARATOR {
+            frames.push(build_frame(&mut rows, start, default_color, &legend)?);
+            start = number;
+        } else {
+            rows.push(line.trim_end());
+        }
+    }
+    frames.push(build_frame(&mut rows, start, default_color, &legend)?);
+
+    Ok(AnimatedSprite::new(frames, ticks_per_frame))
+}
+
+enum Key<'a> {
+    Char(char),
+    Name(&'a str),
+}
+
+/// Splits a header line into its key and value
+fn split_entry(line: &str) -> Option<(Key<'_>, &str)> {
+    let mut chars = line.chars();
+    if let (Some('\''), Some(ch), Some('\'')) = (chars.next(), chars.next(), chars.next()) {
+        let value = chars.as_str().trim_start().strip_prefix('=')?;
+        return Some((Key::Char(ch), value.trim()));
+    }
+
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    let mut key_chars = key.chars();
+    let key = match (key_chars.next(), key_chars.next()) {
+        (Some(ch), None) => Key::Char(ch),
+        (Some(_), Some(_)) => Key::Name(key),
+        (None, _) => return None,
+    };
+    Some((key, value.trim()))
+}
+
+fn parse_color(name: &str, line: usize) -> Result<Color, AssetError> {
+    name.parse().map_err(|_| AssetError::Color {
+        line,
+        name: name.to_string(),
+    })
+}
+
+fn build_frame(
+    rows: &mut Vec<&str>,
+    separator: usize,
+    default_color: Color,
+    legend: &HashMap<char, Color>,
+) -> Result<Sprite, AssetError> {
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+    if rows.iter().all(|row| row.trim().is_empty()) {
+        return Err(AssetError::EmptyFrame { line: separator });
+    }
+    let sprite = Sprite::from_rows_with(rows, |ch| legend.get(&ch).copied().unwrap_or(default_color));
+    rows.clear();
+    Ok(sprite)
+}
+
+/// Reasons a sprite asset could not be loaded
+#[derive(Debug)]
+pub enum AssetError {
+    Io(io::Error),
+    /// A header line that is not `key = value`
+    Syntax { line: usize },
+    /// A header key that is neither a setting nor a single character
+    UnknownKey { line: usize, key: String },
+    /// A color name that [`Color`] does not know
+    Color { line: usize, name: String },
+    /// `frame_ticks` is not a positive number
+    FrameTicks { line: usize },
+    /// A frame without any visible cells; `line` is its `---` line
+    EmptyFrame { line: usize },
+    /// The file has no `---` line
+    NoFrames,
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Io(err) => write!(f, "cannot read sprite: {}", err),
+            AssetError::Syntax { line } => write!(f, "line {}: expected 'key = value'", line),
+            AssetError::UnknownKey { line, key } => write!(f, "line {}: unknown key '{}'", line, key),
+            AssetError::Color { line, name } => write!(f, "line {}: unknown color '{}'", line, name),
+            AssetError::FrameTicks { line } => write!(f, "line {}: frame ticks should be positive", line),
+            AssetError::EmptyFrame { line } => write!(f, "line {}: frame is empty", line),
+            AssetError::NoFrames => f.write_str("sprite has no frames, start one with '---'"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legend_and_quoted_keys() {
+        let sprite = parse_sprite(
+            "# stripes\n\
+             color = blue\n\
+             '=' = red\n\
+             '#' = #00ff00\n\
+             frame_ticks = 3\n\
+             ---\n\
+             =#x\n",
+        )
+        .unwrap();
+
+        let frame = sprite.frame_at(0);
+        assert_eq!(frame.get(0, 0), Some(('=', Color::Red)));
+        assert_eq!(frame.get(1, 0), Some(('#', Color::Rgb { r: 0, g: 255, b: 0 })));
+        assert_eq!(frame.get(2, 0), Some(('x', Color::Blue)));
+        assert_eq!(sprite.get_ticks_per_frame(), 3);
+    }
+
+    #[test]
+    fn test_frames_keep_leading_spaces() {
+        let sprite = parse_sprite("---\n  o\n\n---\no\n\n\n").unwrap();
+
+        let frames = sprite.get_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[0].get_width(), frames[0].get_height()), (3, 1));
+        assert!(frames[0].is_solid(2, 0));
+        assert_eq!((frames[1].get_width(), frames[1].get_height()), (1, 1));
+    }
+
+    #[test]
+    fn test_errors_point_at_lines() {
+        assert!(matches!(parse_sprite("o\n"), Err(AssetError::Syntax { line: 1 })));
+        assert!(matches!(
+            parse_sprite("\nsize = 3\n---\no"),
+            Err(AssetError::UnknownKey { line: 2, .. })
+        ));
+        assert!(matches!(
+            parse_sprite("o = plaid\n---\no"),
+            Err(AssetError::Color { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_sprite("frame_ticks = 0\n---\no"),
+            Err(AssetError::FrameTicks { line: 1 })
+        ));
+        assert!(matches!(
+            parse_sprite("---\no\n---\n   \n"),
+            Err(AssetError::EmptyFrame { line: 3 })
+        ));
+        assert!(matches!(parse_sprite("color = red\n"), Err(AssetError::NoFrames)));
+    }
+}
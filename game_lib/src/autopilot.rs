@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use crate::animation::Animation;
+use crate::clock::Clock;
+use crate::collision::Aabb;
+use crate::game::GameSession;
+use crate::simulation::Control;
+use crate::solvability::Forecast;
+use crate::sprite::Sprite;
+
+/// Updates the autopilot looks ahead by default
+pub const LOOKAHEAD: usize = 40;
+
+/// A reference player that flies the bird through the obstacles on screen
+///
+/// Before every update the autopilot plays the next few updates in its head:
+/// the bird with the game's own physics, the obstacles as a [`Forecast`]
+/// sees them. It lets the bird fall whenever some way of jumping afterwards
+/// still gets it through, and jumps when only jumping now does. Obstacles
+/// that have not spawned yet are unknown to it.
+///
+/// Collisions are tested with the bird's current sprite frame.
+///
+/// # Example
+/// ```
+/// use game_lib::autopilot::Autopilot;
+/// use game_lib::config::GameConfig;
+/// use game_lib::difficulty::DifficultyCurve;
+/// use game_lib::simulation::Simulation;
+///
+/// let config = GameConfig {
+///     difficulty: DifficultyCurve::easy(),
+///     ..GameConfig::default()
+/// };
+/// let autopilot = Autopilot::new();
+/// let mut sim = Simulation::new((80, 24), config, 3);
+/// for _ in 0..500 {
+///     sim.step(autopilot.decide(sim.get_animation(), sim.get_session()));
+/// }
+/// assert!(!sim.is_done());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Autopilot {
+    lookahead: usize,
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Autopilot {
+    /// Creates an autopilot that looks [`LOOKAHEAD`] updates ahead
+    pub fn new() -> Self {
+        Self::with_lookahead(LOOKAHEAD)
+    }
+
+    /// Creates an autopilot that looks `lookahead` updates ahead
+    pub fn with_lookahead(lookahead: usize) -> Self {
+        Self { lookahead }
+    }
+
+    /// Returns how many updates ahead the autopilot looks
+    pub fn get_lookahead(&self) -> usize {
+        self.lookahead
+    }
+
+    /// Decides what to do before the next update of `animation`
+    ///
+    /// If no way through is in sight, the bird heads for the middle of the
+    /// screen and hopes for the best.
+    pub fn decide<C: Clock>(&self, animation: &Animation<C>, session: &GameSession) -> Control {
+        let plan = Plan::new(animation, session, self.lookahead);
+        let (y, velocity) = plan.start;
+        let mut dead_ends = HashSet::new();
+        if plan.survives(0, y, velocity, &mut dead_ends) {
+            return Control::Idle;
+        }
+        let jump_velocity = animation.get_config().jump_velocity;
+        if plan.survives(0, y, jump_velocity, &mut dead_ends) {
+            return Control::Jump;
+        }
+
+        let middle = (animation.get_screen_size().1 / 2) as f32;
+        if y > middle {
+            Control::Jump
+        } else {
+            Control::Idle
+        }
+    }
+}
+
+/// The updates ahead as the autopilot sees them
+struct Plan<'a, C: Clock> {
+    animation: &'a Animation<C>,
+    sprite: &'a Sprite,
+    bird_x: f32,
+    start: (f32, f32),
+    jump_velocity: f32,
+    /// Obstacle boxes after each of the updates ahead
+    obstacles: Vec<Vec<Aabb>>,
+}
+
+impl<'a, C: Clock> Plan<'a, C> {
+    fn new(animation: &'a Animation<C>, session: &GameSession, lookahead: usize) -> Self {
+        let bird = animation.get_bird();
+        let forecast = Forecast::new(
+            animation.get_rectangles(),
+            bird,
+            animation.get_config().difficulty,
+            session,
+        );
+        let obstacles = forecast.take(lookahead).collect();
+
+        let (bird_x, y) = bird.get_position();
+        Self {
+            animation,
+            sprite: bird.get_sprite(),
+            bird_x: bird_x.floor(),
+            start: (y, bird.get_velocity()),
+            jump_velocity: animation.get_config().jump_velocity,
+            obstacles,
+        }
+    }
+
+    /// Returns `true` if the bird gets through the updates from `tick` on,
+    /// entering update `tick` at height `y` with `velocity` already set
+    ///
+    /// States known to crash are kept in `dead_ends`, so each is only
+    /// explored once.
+    fn survives(&self, tick: usize, y: f32, velocity: f32, dead_ends: &mut HashSet<(usize, u32, u32)>) -> bool {
+        if tick == self.obstacles.len() {
+            return true;
+        }
+        let state = (tick, y.to_bits(), velocity.to_bits());
+        if dead_ends.contains(&state) {
+            return false;
+        }
+
+        let (y, next_velocity) = self.animation.get_bird().fall(y, velocity);
+        let hit = self.obstacles[tick]
+            .iter()
+            .any(|obstacle| self.sprite.penetration(self.bird_x, y.floor(), obstacle).is_some());
+        // Falling first keeps the bird low and leaves jumps for when they count
+        let through = !hit
+            && (self.survives(tick + 1, y, next_velocity, dead_ends)
+                || self.survives(tick + 1, y, self.jump_velocity, dead_ends));
+        if !through {
+            dead_ends.insert(state);
+        }
+        through
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use crate::difficulty::DifficultyCurve;
+    use crate::simulation::Simulation;
+
+    #[test]
+    fn test_autopilot_keeps_flying() {
+        let autopilot = Autopilot::new();
+        let config = GameConfig {
+            difficulty: DifficultyCurve::easy(),
+            ..GameConfig::default()
+        };
+        for seed in 0..3 {
+            let mut sim = Simulation::new((80, 24), config, seed);
+            let end = sim.run_with(2000, |_| Control::Idle);
+            assert!(end.done, "an idle bird should crash, seed {}", seed);
+
+            sim.reset(seed);
+            for _ in 0..2000 {
+                sim.step(autopilot.decide(sim.get_animation(), sim.get_session()));
+            }
+            let observation = sim.observe();
+            assert!(!observation.done, "seed {} crashed at tick {}", seed, observation.tick);
+            assert!(observation.level > 5);
+        }
+    }
+}
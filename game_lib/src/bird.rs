@@ -1,20 +1,29 @@
-use crossterm::style::Color;
-
-use crate::GRAVITY;
-use crate::JUMP_VELOCITY;
-use crate::MAX_VELOCITY;
-use crate::UPDATE_INTERVAL;
+use crate::collision::{Aabb, Collider, Side};
+use crate::config::GameConfig;
+use crate::render::Color;
+use crate::sprite::{AnimatedSprite, Sprite};
 
 /// Represents the player-controlled bird in the game
 /// 
 /// The bird moves with physics-based motion, affected by gravity and jump impulses.
 /// It maintains a constant x position but can move vertically.
-#[derive(Debug)]
+///
+/// Positions are kept in continuous world units, one unit per terminal cell;
+/// they are only rounded down to cells for drawing and collision.
+///
+/// Drawing and collision share the bird's [`Sprite`], so only the cells that
+/// are visible can be hit. The sprite may be animated, e.g. to flap its wings;
+/// it advances one tick per update.
+#[derive(Debug, Clone)]
 pub struct Bird {
-    x: u16,
-    y: u16,
+    x: f32,
+    y: f32,
+    previous_y: f32,
     velocity: f32,
     screen_size: (u16, u16),
+    config: GameConfig,
+    sprite: AnimatedSprite,
+    ticks: u32,
 }
 
 impl Bird {
@@ -22,51 +31,118 @@ impl Bird {
     /// 
     /// # Arguments
     /// * `screen_size` - Terminal dimensions (width, height)
+    /// * `config` - Physics parameters and the bird's x position
     /// 
     /// # Example
     /// ```
-    /// use game_lib::bird::Bird;
+    /// use game_lib::{bird::Bird, config::GameConfig};
     /// 
     /// let screen_size = (80, 24);
-    /// let bird = Bird::new(screen_size);
+    /// let bird = Bird::new(screen_size, &GameConfig::default());
     /// assert_eq!(bird.get_leftmost_x(), 25); // Fixed x position
     /// ```
-    pub fn new(screen_size: (u16, u16)) -> Self {
+    pub fn new(screen_size: (u16, u16), config: &GameConfig) -> Self {
+        Self::with_sprite(screen_size, config, Self::default_sprite())
+    }
+
+    /// Creates a new bird drawn with a custom sprite, e.g. one loaded with
+    /// [`load_sprite`](crate::asset::load_sprite)
+    pub fn with_sprite(screen_size: (u16, u16), config: &GameConfig, sprite: AnimatedSprite) -> Self {
+        let y = (screen_size.1 / 2) as f32; // Start in middle of screen
         Self {
-            x: 25, // Fixed x position on the left
-            y: screen_size.1 / 2, // Start in middle of screen
+            x: config.bird_x as f32, // Fixed x position on the left
+            y,
+            previous_y: y,
             velocity: 0.0,
             screen_size,
+            config: *config,
+            sprite,
+            ticks: 0,
         }
     }
 
+    /// Returns the built-in yellow bird
+    pub fn default_sprite() -> AnimatedSprite {
+        Sprite::from_rows(&["   |>", "|::| "], Color::Yellow).into()
+    }
+
     /// Updates the bird's position based on physics
     /// 
     /// Applies gravity and updates position using basic physics equations.
     /// Prevents the bird from moving outside screen bounds.
     pub fn update(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+        (self.y, self.velocity) = self.fall(self.y, self.velocity);
+    }
+
+    /// Returns where one update takes the bird from height `y` at `velocity`
+    ///
+    /// This is the physics of [`Bird::update`] without moving the bird, to
+    /// look ahead at its path. Returns the new height and velocity.
+    pub fn fall(&self, y: f32, velocity: f32) -> (f32, f32) {
         // Apply gravity and clamp to terminal velocity
-        self.velocity = self.velocity.min(MAX_VELOCITY);
+        let GameConfig { gravity, max_velocity, update_interval, .. } = self.config;
+        let velocity = velocity.min(max_velocity);
         
         // Update position using physics equation: y = y0 + v0*t + 0.5*a*t^2
-        let delta_y = self.velocity*UPDATE_INTERVAL + 0.5 * GRAVITY*UPDATE_INTERVAL*UPDATE_INTERVAL;
-        let new_y = self.y as f32 + delta_y;
-        
-        if new_y > (self.screen_size.1 - 4) as f32 {
-            self.velocity = 0.0;
-            self.y = (self.screen_size.1 - 4) as u16;
+        let delta_y = velocity*update_interval + 0.5 * gravity*update_interval*update_interval;
+        let new_y = y + delta_y;
+        let floor = self.get_floor();
+
+        if new_y > floor {
+            (floor, 0.0)
         } else if new_y < 1.0 {
-            self.velocity = 0.0;
-            self.y = 1;
+            (1.0, 0.0)
         } else {
-            self.velocity += GRAVITY;
-            self.y = new_y as u16;
+            (new_y, velocity + gravity)
         }
     }
 
-    /// Makes the bird jump by setting its velocity to JUMP_VELOCITY
+    /// Makes the bird jump by setting its velocity to the configured jump velocity
     pub fn jump(&mut self) {
-        self.velocity = JUMP_VELOCITY;
+        self.velocity = self.config.jump_velocity;
+    }
+
+    /// Moves the bird onto a screen of a new size
+    ///
+    /// The bird's height is scaled with the screen's, so it keeps its place
+    /// relative to the obstacles; see [`Rect::resize`](crate::rect::Rect::resize).
+    pub fn resize(&mut self, screen_size: (u16, u16)) {
+        let scale = screen_size.1 as f32 / self.screen_size.1 as f32;
+        self.screen_size = screen_size;
+        self.y = (self.y * scale).clamp(1.0, self.get_floor());
+        self.previous_y = self.y;
+    }
+
+    /// Returns the lowest the bird's top edge can go
+    ///
+    /// Leaves a row between the bird and the bottom border, however tall
+    /// the tallest frame of its sprite is.
+    pub fn get_floor(&self) -> f32 {
+        let height = self.sprite.get_frames().iter().map(Sprite::get_height).max().unwrap_or(1);
+        self.screen_size.1.saturating_sub(2 + height).max(1) as f32
+    }
+
+    /// Moves the bird to the nearest height where it touches none of `obstacles`
+    ///
+    /// A resize can scale obstacles over the bird; this puts it back in the
+    /// open. The bird stays where it is if no height on screen is clear.
+    pub fn clear_of(&mut self, obstacles: &[Aabb]) {
+        let (x, y) = (self.x.floor(), self.y);
+        let floor = self.get_floor();
+        let sprite = self.get_sprite();
+        let clear = |y: f32| {
+            obstacles
+                .iter()
+                .all(|obstacle| sprite.penetration(x, y.floor(), obstacle).is_none())
+        };
+        let open = (0..self.screen_size.1)
+            .flat_map(|rows| [y - rows as f32, y + rows as f32])
+            .find(|&y| (1.0..=floor).contains(&y) && clear(y));
+        if let Some(y) = open {
+            self.y = y;
+            self.previous_y = y;
+        }
     }
 
     /// Returns a vector of points representing the bird for drawing
@@ -76,85 +152,124 @@ impl Bird {
     /// - y coordinate
     /// - character to draw
     /// - color of the point
+    ///
+    /// Empty cells of the sprite are left out, so the bird never blanks out
+    /// what is behind it.
     pub fn draw(&self) -> Vec<(u16, u16, char, Color)> {
-        let mut points = Vec::new();
-        let color = Color::Yellow;
-
-        // Bird shape using ASCII characters
-        let bird_shape = [
-            (0, 0, ' '),
-            (1, 0, ' '),
-            (2, 0, ' '),
-            (3, 0, '|'),
-            (4, 0, '>'),
-            (0, 1, '|'),
-            (1, 1, ':'),
-            (2, 1, ':'),
-            (3, 1, '|'),
-            (4, 1, ' '),
-        ];
+        self.get_sprite().draw(self.get_leftmost_x(), self.get_top_y())
+    }
 
-        for (dx, dy, char) in bird_shape {
-            points.push((self.x + dx, self.y + dy, char, color));
-        }
+    /// Returns the points of the bird a fraction `alpha` of the way through
+    /// the last step
+    ///
+    /// Only the height is blended between the start of the step and now;
+    /// collisions always use the current position.
+    pub fn draw_at(&self, alpha: f32) -> Vec<(u16, u16, char, Color)> {
+        let y = self.y - (self.y - self.previous_y) * (1.0 - alpha);
+        self.get_sprite().draw(self.get_leftmost_x(), y as u16)
+    }
+
+    /// Makes the current position the start of the next step
+    pub fn start_step(&mut self) {
+        self.previous_y = self.y;
+    }
+
+    /// Returns the sprite frame currently used to draw the bird and test it for collisions
+    pub fn get_sprite(&self) -> &Sprite {
+        self.sprite.frame_at(self.ticks)
+    }
 
-        points
+    /// Returns the sprite frame the bird will have after `updates` more updates
+    pub fn get_sprite_after(&self, updates: u32) -> &Sprite {
+        self.sprite.frame_at(self.ticks.wrapping_add(updates))
+    }
+
+    /// Returns the bird's bounding box
+    ///
+    /// The box is snapped to the cells the bird is drawn in, so collisions
+    /// match what is on screen.
+    pub fn get_bounds(&self) -> Aabb {
+        self.get_sprite().get_bounds(self.x.floor(), self.y.floor())
+    }
+
+    /// Returns the world position of the bird's top-left corner
+    pub fn get_position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// Returns the current vertical velocity (negative is up)
+    pub fn get_velocity(&self) -> f32 {
+        self.velocity
     }
 
     /// Returns the rightmost x coordinate of the bird
     pub fn get_rightmost_x(&self) -> u16 {
-        self.x + 4 // Bird width is 5 characters (0-4)
+        self.get_leftmost_x() + self.get_sprite().get_width() - 1
     }
  
     /// Returns the bottom y coordinate of the bird
     pub fn get_bottom_y(&self) -> u16 {
-        self.y + 2 // Bird height is 3 characters (0-2)
+        self.get_top_y() + self.get_sprite().get_height() - 1
     }
 
     /// Returns the leftmost x coordinate of the bird
     pub fn get_leftmost_x(&self) -> u16 {
-        self.x
+        self.x as u16
+    }
+
+    /// Returns the row of the bird's top edge
+    pub fn get_top_y(&self) -> u16 {
+        self.y as u16
+    }
+}
+
+impl Collider for Bird {
+    /// Tests only the solid cells of the bird's sprite
+    fn penetration(&self, obstacle: &Aabb) -> Option<(Side, f32)> {
+        self.get_sprite().penetration(self.x.floor(), self.y.floor(), obstacle)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{JUMP_VELOCITY, MAX_VELOCITY};
 
     #[test]
     fn test_bird_creation() {
         let screen_size = (80, 24);
-        let bird = Bird::new(screen_size);
+        let bird = Bird::new(screen_size, &GameConfig::default());
         
-        assert_eq!(bird.x, 25);
-        assert_eq!(bird.y, screen_size.1 / 2);
+        assert_eq!(bird.x, 25.0);
+        assert_eq!(bird.y, (screen_size.1 / 2) as f32);
         assert_eq!(bird.velocity, 0.0);
     }
 
     #[test]
     fn test_bird_boundaries() {
         let screen_size = (80, 24);
-        let mut bird = Bird::new(screen_size);
+        let mut bird = Bird::new(screen_size, &GameConfig::default());
         
         // Test floor collision
-        bird.y = screen_size.1 - 1;
+        bird.y = (screen_size.1 - 1) as f32;
         bird.velocity = MAX_VELOCITY;
         bird.update();
-        assert_eq!(bird.y, screen_size.1 - 4);
+        assert_eq!(bird.y, (screen_size.1 - 4) as f32);
+        assert_eq!(bird.get_bottom_y(), screen_size.1 - 3);
         assert_eq!(bird.velocity, 0.0);
 
         // Test ceiling collision
-        bird.y = 0;
+        bird.y = 0.0;
         bird.velocity = JUMP_VELOCITY;
         bird.update();
-        assert_eq!(bird.y, 1);
+        assert_eq!(bird.y, 1.0);
         assert_eq!(bird.velocity, 0.0);
     }
 
     #[test]
     fn test_bird_jump() {
         let screen_size = (80, 24);
-        let mut bird = Bird::new(screen_size);
+        let mut bird = Bird::new(screen_size, &GameConfig::default());
         let initial_y = bird.y;
         
         bird.jump();
@@ -165,21 +280,83 @@ mod tests {
         assert!(bird.y < initial_y);
     }
 
+    #[test]
+    fn test_bird_keeps_fractional_motion() {
+        let screen_size = (80, 24);
+        let mut bird = Bird::new(screen_size, &GameConfig::default());
+
+        // Half a cell of fall is kept rather than truncated away
+        bird.update();
+        assert_eq!(bird.get_position().1, 12.5);
+        assert_eq!(bird.get_top_y(), 12);
+
+        bird.update();
+        assert_eq!(bird.get_position().1, 14.0);
+        assert_eq!(bird.get_velocity(), 2.0);
+    }
+
+    #[test]
+    fn test_bird_flaps_through_frames() {
+        let frames = vec![
+            Sprite::from_rows(&["   |>", "|^^| "], Color::Yellow),
+            Sprite::from_rows(&["   |>", "|vv| "], Color::Yellow),
+        ];
+        let mut bird = Bird::with_sprite((80, 24), &GameConfig::default(), AnimatedSprite::new(frames, 1));
+
+        let wing = |bird: &Bird| bird.get_sprite().get(1, 1).unwrap().0;
+        assert_eq!(wing(&bird), '^');
+        bird.update();
+        assert_eq!(wing(&bird), 'v');
+        bird.update();
+        assert_eq!(wing(&bird), '^');
+    }
+
     #[test]
     fn test_bird_drawing() {
         let screen_size = (80, 24);
-        let bird = Bird::new(screen_size);
+        let bird = Bird::new(screen_size, &GameConfig::default());
         let points = bird.draw();
         
-        // Check that we have the right number of points
-        assert_eq!(points.len(), 10);
+        // Only the six solid cells of the sprite are drawn
+        assert_eq!(points.len(), 6);
         
         // Check that all points are within bird bounds
         for (x, y, _, _) in points {
             assert!(x >= bird.get_leftmost_x());
             assert!(x <= bird.get_rightmost_x());
-            assert!(y >= bird.y);
+            assert!(y >= bird.get_top_y());
             assert!(y <= bird.get_bottom_y());
         }
     }
+
+    #[test]
+    fn test_bird_cleared_of_obstacles() {
+        let mut bird = Bird::new((80, 24), &GameConfig::default());
+        // Rows 0 to 12 are blocked, the bird's top row among them
+        bird.clear_of(&[Aabb::new(20.0, 0.0, 10.0, 13.0)]);
+        assert_eq!(bird.get_position().1, 13.0);
+
+        // Nothing is clear, so the bird stays put
+        bird.clear_of(&[Aabb::new(20.0, 0.0, 10.0, 24.0)]);
+        assert_eq!(bird.get_position().1, 13.0);
+    }
+
+    #[test]
+    fn test_tall_bird_stays_above_floor() {
+        let sprite = Sprite::from_rows(&[" /\\", "<oo>", "|  |", " \\/"], Color::Yellow);
+        let screen_size = (80, 24);
+        let mut bird = Bird::with_sprite(screen_size, &GameConfig::default(), sprite.into());
+        assert_eq!(bird.get_floor(), 18.0);
+
+        bird.velocity = MAX_VELOCITY;
+        for _ in 0..10 {
+            bird.update();
+        }
+        assert_eq!(bird.get_position().1, 18.0);
+        assert_eq!(bird.get_bottom_y(), screen_size.1 - 3);
+
+        // Shrinking the screen keeps all four rows above the border too
+        bird.resize((80, 16));
+        assert_eq!(bird.get_bottom_y(), 13);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A source of monotonic time for the game
+///
+/// Time is reported as the duration elapsed since the clock's epoch,
+/// which lets the game run against either wall time or a virtual clock.
+pub trait Clock {
+    /// Returns the time elapsed since the clock's epoch
+    fn now(&self) -> Duration;
+}
+
+/// Clock backed by the system's monotonic clock
+///
+/// The epoch is the moment the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    /// Creates a new system clock starting at the current instant
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Virtual clock that only moves when told to
+///
+/// Clones share the same time, so a test can keep one handle and give
+/// another to an [`Animation`](crate::animation::Animation).
+///
+/// # Example
+/// ```
+/// use game_lib::clock::{Clock, ManualClock};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let handle = clock.clone();
+/// handle.advance(Duration::from_millis(250));
+/// assert_eq!(clock.now(), Duration::from_millis(250));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Creates a new manual clock at time zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward by `step`
+    pub fn advance(&self, step: Duration) {
+        self.nanos.fetch_add(step.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// Sets the clock to an absolute time since its epoch
+    pub fn set(&self, time: Duration) {
+        self.nanos.store(time.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_advance() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        clock.advance(Duration::from_millis(40));
+        clock.advance(Duration::from_millis(60));
+        assert_eq!(clock.now(), Duration::from_millis(100));
+
+        clock.set(Duration::from_secs(3));
+        assert_eq!(clock.now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_manual_clock_clones_share_time() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+
+        handle.advance(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_system_clock_is_monotonic() {
+        let clock = SystemClock::new();
+        let first = clock.now();
+        let second = clock.now();
+        assert!(second >= first);
+    }
+}
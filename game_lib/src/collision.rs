@@ -0,0 +1,213 @@
+/// Axis-aligned bounding box in world units
+///
+/// `x` and `y` are the top-left corner; one unit is one terminal cell and
+/// y grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Aabb {
+    /// Creates a box from its top-left corner and size
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Returns the x coordinate of the left edge
+    pub fn left(&self) -> f32 {
+        self.x
+    }
+
+    /// Returns the x coordinate just past the right edge
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// Returns the y coordinate of the top edge
+    pub fn top(&self) -> f32 {
+        self.y
+    }
+
+    /// Returns the y coordinate just past the bottom edge
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Returns `true` if the boxes share any area
+    ///
+    /// Boxes that only touch along an edge do not overlap.
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.left() < other.right()
+            && other.left() < self.right()
+            && self.top() < other.bottom()
+            && other.top() < self.bottom()
+    }
+
+    /// Works out which side of `other` this box ran into and how deep
+    ///
+    /// The side is the one with the shallowest overlap, i.e. the one the
+    /// box would have to be pushed back through to separate them. Ties go to
+    /// the left or right side.
+    ///
+    /// Returns `None` if the boxes do not overlap.
+    pub fn penetration(&self, other: &Aabb) -> Option<(Side, f32)> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        let overlap_x = self.right().min(other.right()) - self.left().max(other.left());
+        let overlap_y = self.bottom().min(other.bottom()) - self.top().max(other.top());
+
+        if overlap_x <= overlap_y {
+            let side = if self.center().0 < other.center().0 { Side::Left } else { Side::Right };
+            Some((side, overlap_x))
+        } else {
+            let side = if self.center().1 < other.center().1 { Side::Top } else { Side::Bottom };
+            Some((side, overlap_y))
+        }
+    }
+
+    /// Returns the smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        Aabb::new(
+            left,
+            top,
+            self.right().max(other.right()) - left,
+            self.bottom().max(other.bottom()) - top,
+        )
+    }
+
+    fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+/// Something that can be tested against obstacle boxes
+pub trait Collider {
+    /// Returns the side of `obstacle` that was hit and how deep, if they overlap
+    fn penetration(&self, obstacle: &Aabb) -> Option<(Side, f32)>;
+}
+
+impl Collider for Aabb {
+    fn penetration(&self, obstacle: &Aabb) -> Option<(Side, f32)> {
+        Aabb::penetration(self, obstacle)
+    }
+}
+
+/// Side of an obstacle that was hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Flew into the obstacle's left face
+    Left,
+    /// Flew into the obstacle's right face
+    Right,
+    /// Came down onto the obstacle's top
+    Top,
+    /// Came up into the obstacle's underside
+    Bottom,
+}
+
+/// A collision between the bird and an obstacle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// Index of the obstacle in [`Animation::get_rectangles`](crate::animation::Animation::get_rectangles)
+    pub entity: usize,
+    /// Side of the obstacle that was hit
+    pub side: Side,
+    /// How far the boxes overlap across that side
+    pub penetration: f32,
+}
+
+/// Finds the first obstacle that `subject` overlaps
+///
+/// # Example
+/// ```
+/// use game_lib::collision::{first_contact, Aabb, Side};
+///
+/// let bird = Aabb::new(0.0, 4.5, 5.0, 3.0);
+/// let obstacles = [Aabb::new(20.0, 0.0, 2.0, 4.0), Aabb::new(1.0, 7.0, 3.0, 5.0)];
+///
+/// let contact = first_contact(&bird, obstacles).unwrap();
+/// assert_eq!(contact.entity, 1);
+/// assert_eq!(contact.side, Side::Top);
+/// assert_eq!(contact.penetration, 0.5);
+/// ```
+pub fn first_contact<C, I>(subject: &C, obstacles: I) -> Option<Contact>
+where
+    C: Collider + ?Sized,
+    I: IntoIterator<Item = Aabb>,
+{
+    obstacles.into_iter().enumerate().find_map(|(entity, obstacle)| {
+        subject
+            .penetration(&obstacle)
+            .map(|(side, penetration)| Contact { entity, side, penetration })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlap_needs_both_axes() {
+        let a = Aabb::new(0.0, 0.0, 4.0, 4.0);
+
+        assert!(a.overlaps(&Aabb::new(3.0, 3.0, 4.0, 4.0)));
+        // Same rows, separate columns
+        assert!(!a.overlaps(&Aabb::new(5.0, 0.0, 4.0, 4.0)));
+        // Same columns, separate rows
+        assert!(!a.overlaps(&Aabb::new(0.0, 6.0, 4.0, 4.0)));
+        // Touching edges are not a hit
+        assert!(!a.overlaps(&Aabb::new(4.0, 0.0, 4.0, 4.0)));
+    }
+
+    #[test]
+    fn test_penetration_sides() {
+        let bird = Aabb::new(10.0, 10.0, 5.0, 3.0);
+
+        // Obstacle scrolling into the bird from the right
+        let wall = Aabb::new(14.0, 5.0, 3.0, 20.0);
+        assert_eq!(bird.penetration(&wall), Some((Side::Left, 1.0)));
+
+        // Bird falling onto a floor block
+        let floor = Aabb::new(8.0, 12.5, 10.0, 10.0);
+        assert_eq!(bird.penetration(&floor), Some((Side::Top, 0.5)));
+
+        // Bird rising into a ceiling block
+        let ceiling = Aabb::new(8.0, 0.0, 10.0, 11.0);
+        assert_eq!(bird.penetration(&ceiling), Some((Side::Bottom, 1.0)));
+
+        // Obstacle that has scrolled past the bird
+        let behind = Aabb::new(7.0, 5.0, 4.0, 20.0);
+        assert_eq!(bird.penetration(&behind), Some((Side::Right, 1.0)));
+
+        assert_eq!(bird.penetration(&Aabb::new(30.0, 0.0, 1.0, 1.0)), None);
+    }
+
+    #[test]
+    fn test_first_contact_reports_index() {
+        let bird = Aabb::new(10.0, 10.0, 5.0, 3.0);
+        let obstacles = [
+            Aabb::new(0.0, 0.0, 2.0, 2.0),
+            Aabb::new(14.0, 0.0, 2.0, 30.0),
+            Aabb::new(10.0, 10.0, 5.0, 3.0),
+        ];
+
+        let contact = first_contact(&bird, obstacles).unwrap();
+        assert_eq!(contact.entity, 1);
+        assert_eq!(contact.side, Side::Left);
+        assert!(first_contact(&bird, []).is_none());
+    }
+
+    #[test]
+    fn test_union() {
+        let a = Aabb::new(0.0, 0.0, 2.0, 2.0);
+        let b = Aabb::new(3.0, -1.0, 1.0, 1.0);
+        assert_eq!(a.union(&b), Aabb::new(0.0, -1.0, 4.0, 3.0));
+    }
+}
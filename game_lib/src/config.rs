@@ -0,0 +1,201 @@
+use std::fmt;
+
+use crate::animation::MIN_SCREEN_SIZE;
+use crate::bird::Bird;
+use crate::difficulty::DifficultyCurve;
+use crate::score::ScoreRules;
+use crate::sprite::AnimatedSprite;
+use crate::{GRAVITY, JUMP_VELOCITY, MAX_VELOCITY, UPDATE_INTERVAL};
+
+/// Default horizontal position of the bird
+pub const BIRD_X: u16 = 25;
+
+/// Smallest gap a pipe pair ever gets, leaving the two-row bird room to move
+pub const MIN_PIPE_GAP: u16 = 4;
+
+/// Tunable parameters of a game
+///
+/// The defaults reproduce the library's original feel. Use
+/// [`GameConfig::validate`] before handing a custom config to
+/// [`Animation::new`](crate::animation::Animation::new).
+///
+/// # Example
+/// ```
+/// use game_lib::config::GameConfig;
+///
+/// let config = GameConfig {
+///     gravity: 0.5,
+///     jump_velocity: -2.0,
+///     ..GameConfig::default()
+/// };
+/// assert!(config.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameConfig {
+    /// Downward acceleration applied to the bird every update
+    pub gravity: f32,
+    /// Vertical velocity set when the bird jumps (negative is up)
+    pub jump_velocity: f32,
+    /// Simulated time per physics update
+    pub update_interval: f32,
+    /// Terminal falling speed of the bird
+    pub max_velocity: f32,
+    /// Fixed horizontal position of the bird
+    pub bird_x: u16,
+    /// Obstacle speed, spawn rate, sizes and gaps for every level
+    pub difficulty: DifficultyCurve,
+    /// Points for passing obstacles and the close-call bonus
+    pub score: ScoreRules,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            gravity: GRAVITY,
+            jump_velocity: JUMP_VELOCITY,
+            update_interval: UPDATE_INTERVAL,
+            max_velocity: MAX_VELOCITY,
+            bird_x: BIRD_X,
+            difficulty: DifficultyCurve::default(),
+            score: ScoreRules::default(),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Checks that the parameters describe a playable game
+    ///
+    /// # Errors
+    /// Returns the first violated invariant.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.gravity > 0.0 && self.gravity.is_finite()) {
+            return Err(ConfigError::Gravity);
+        }
+        if !(self.jump_velocity < 0.0 && self.jump_velocity.is_finite()) {
+            return Err(ConfigError::JumpVelocity);
+        }
+        if !(self.update_interval > 0.0 && self.update_interval.is_finite()) {
+            return Err(ConfigError::UpdateInterval);
+        }
+        if !(self.max_velocity > self.gravity && self.max_velocity.is_finite()) {
+            return Err(ConfigError::MaxVelocity);
+        }
+        self.validate_bird(&Bird::default_sprite())?;
+        self.difficulty.validate()?;
+        if self.score.max_combo == 0 {
+            return Err(ConfigError::MaxCombo);
+        }
+        Ok(())
+    }
+
+    /// Checks that a bird drawn with `sprite` fits on the smallest screen
+    ///
+    /// [`GameConfig::validate`] checks the built-in bird; check custom
+    /// sprites with this before using them.
+    ///
+    /// # Errors
+    /// Returns [`ConfigError::BirdX`] if the bird would reach past
+    /// [`MIN_SCREEN_SIZE`].
+    pub fn validate_bird(&self, sprite: &AnimatedSprite) -> Result<(), ConfigError> {
+        let width = sprite.get_frames().iter().map(|frame| frame.get_width()).max().unwrap_or(0);
+        if self.bird_x as u32 + width as u32 > MIN_SCREEN_SIZE.0 as u32 {
+            return Err(ConfigError::BirdX);
+        }
+        Ok(())
+    }
+}
+
+/// A violated [`GameConfig`] invariant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Gravity must be positive
+    Gravity,
+    /// Jump velocity must be negative (upward)
+    JumpVelocity,
+    /// Update interval must be positive
+    UpdateInterval,
+    /// Max velocity must be greater than gravity
+    MaxVelocity,
+    /// The bird must fit inside the smallest screen
+    BirdX,
+    /// Obstacles must spawn at a non-zero interval
+    RectInterval,
+    /// Obstacle widths must be a non-empty range of at least 1 cell, no
+    /// wider than the smallest screen
+    ObstacleWidth,
+    /// Obstacle heights must be a non-empty range of at least 1 row
+    ObstacleHeight,
+    /// Obstacles must scroll left at a positive speed
+    ScrollSpeed,
+    /// Pipe gaps must leave room for the bird
+    PipeGap,
+    /// The difficulty curve must ramp up at a rate in (0, 1]
+    DifficultyRate,
+    /// The combo multiplier must be at least 1
+    MaxCombo,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ConfigError::Gravity => "gravity should be positive",
+            ConfigError::JumpVelocity => "jump velocity should be negative (upward)",
+            ConfigError::UpdateInterval => "update interval should be positive",
+            ConfigError::MaxVelocity => "max velocity should be greater than gravity",
+            ConfigError::BirdX => "bird x should leave the whole bird inside 40 columns",
+            ConfigError::RectInterval => "rect interval should be positive",
+            ConfigError::ObstacleWidth => "obstacle widths should be a non-empty range from 1 to 40",
+            ConfigError::ObstacleHeight => "obstacle heights should be a non-empty range starting at 1 or more",
+            ConfigError::ScrollSpeed => "scroll speed should be positive",
+            ConfigError::PipeGap => "pipe gaps should be a non-empty range starting at 4 or more",
+            ConfigError::DifficultyRate => "difficulty rate should be in (0, 1]",
+            ConfigError::MaxCombo => "max combo should be at least 1",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Color;
+    use crate::sprite::Sprite;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(GameConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_bird_fits_on_screen() {
+        let config = GameConfig { bird_x: 35, ..GameConfig::default() };
+        assert_eq!(config.validate(), Ok(()));
+
+        // A wider custom bird no longer fits at the same place
+        let wide = Sprite::from_rows(&["<======>"], Color::Yellow).into();
+        assert_eq!(config.validate_bird(&wide), Err(ConfigError::BirdX));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        let base = GameConfig::default();
+
+        let cases = [
+            (GameConfig { gravity: 0.0, ..base }, ConfigError::Gravity),
+            (GameConfig { gravity: f32::NAN, ..base }, ConfigError::Gravity),
+            (GameConfig { jump_velocity: 1.0, ..base }, ConfigError::JumpVelocity),
+            (GameConfig { update_interval: 0.0, ..base }, ConfigError::UpdateInterval),
+            (GameConfig { max_velocity: 0.5, ..base }, ConfigError::MaxVelocity),
+            (GameConfig { bird_x: 36, ..base }, ConfigError::BirdX),
+            (GameConfig { bird_x: u16::MAX, ..base }, ConfigError::BirdX),
+            (GameConfig { difficulty: DifficultyCurve { rate: 0.0, ..base.difficulty }, ..base }, ConfigError::DifficultyRate),
+            (GameConfig { score: ScoreRules { max_combo: 0, ..base.score }, ..base }, ConfigError::MaxCombo),
+        ];
+
+        for (config, expected) in cases {
+            assert_eq!(config.validate(), Err(expected));
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::animation::MIN_SCREEN_SIZE;
+use crate::config::{ConfigError, MIN_PIPE_GAP};
+use crate::RECT_INTERVAL;
+
+/// Rows an obstacle always leaves free, so the bird has room to fly past
+pub const MIN_OPEN_ROWS: u16 = 11;
+
+/// How hard the game is at one level
+///
+/// Width, height and gap ranges are inclusive; new obstacles pick a random
+/// size within them. Obstacles are never wider than the smallest screen,
+/// [`MIN_SCREEN_SIZE`]. Heights are also capped by the screen so that at least
+/// [`MIN_OPEN_ROWS`] rows stay open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    /// Distance obstacles move left per update, in cells (may be fractional)
+    pub scroll_speed: f32,
+    /// Time between obstacle spawns in ms
+    pub rect_interval: u64,
+    /// Narrowest obstacle, in cells
+    pub min_width: u16,
+    /// Widest obstacle, in cells
+    pub max_width: u16,
+    /// Shortest floor or ceiling block, in rows
+    pub min_height: u16,
+    /// Tallest floor or ceiling block, in rows
+    pub max_height: u16,
+    /// Narrowest gap between a pipe pair's halves, in rows
+    pub min_gap: u16,
+    /// Widest gap between a pipe pair's halves, in rows
+    pub max_gap: u16,
+}
+
+impl Difficulty {
+    /// Checks that obstacles can be generated from these values
+    ///
+    /// # Errors
+    /// Returns the first violated invariant.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(self.scroll_speed > 0.0 && self.scroll_speed.is_finite()) {
+            return Err(ConfigError::ScrollSpeed);
+        }
+        if self.rect_interval == 0 {
+            return Err(ConfigError::RectInterval);
+        }
+        if !(1 <= self.min_width && self.min_width <= self.max_width && self.max_width <= MIN_SCREEN_SIZE.0) {
+            return Err(ConfigError::ObstacleWidth);
+        }
+        if !(1 <= self.min_height && self.min_height <= self.max_height) {
+            return Err(ConfigError::ObstacleHeight);
+        }
+        if !(MIN_PIPE_GAP <= self.min_gap && self.min_gap <= self.max_gap) {
+            return Err(ConfigError::PipeGap);
+        }
+        Ok(())
+    }
+
+    /// Returns the values a fraction `t` of the way from `self` to `other`
+    fn lerp(&self, other: &Difficulty, t: f32) -> Difficulty {
+        let lerp = |from: f64, to: f64| from + (to - from) * t as f64;
+        let int = |from: u16, to: u16| lerp(from as f64, to as f64).round() as u16;
+        Difficulty {
+            scroll_speed: lerp(self.scroll_speed as f64, other.scroll_speed as f64) as f32,
+            rect_interval: lerp(self.rect_interval as f64, other.rect_interval as f64).round() as u64,
+            min_width: int(self.min_width, other.min_width),
+            max_width: int(self.max_width, other.max_width),
+            min_height: int(self.min_height, other.min_height),
+            max_height: int(self.max_height, other.max_height),
+            min_gap: int(self.min_gap, other.min_gap),
+            max_gap: int(self.max_gap, other.max_gap),
+        }
+    }
+}
+
+/// Maps a level to the [`Difficulty`] of that level
+///
+/// Level 1 plays at `start`. Every level up covers `rate` of the distance
+/// still left to `limit`, so the game ramps up quickly at first and then
+/// levels off without ever going past `limit`.
+///
+/// Use one of the presets, or build a custom curve from its fields.
+///
+/// # Example
+/// ```
+/// use game_lib::difficulty::DifficultyCurve;
+///
+/// let normal = DifficultyCurve::normal();
+/// let custom = DifficultyCurve {
+///     rate: 0.5,
+///     ..DifficultyCurve::normal()
+/// };
+/// assert!(custom.validate().is_ok());
+///
+/// // Both start out the same, but the custom curve speeds up faster
+/// assert_eq!(custom.at_level(1), normal.at_level(1));
+/// assert!(custom.at_level(3).scroll_speed > normal.at_level(3).scroll_speed);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyCurve {
+    /// Difficulty at level 1
+    pub start: Difficulty,
+    /// Difficulty the curve approaches at high levels
+    pub limit: Difficulty,
+    /// Fraction of the remaining way to `limit` covered per level, in (0, 1]
+    pub rate: f32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
+impl DifficultyCurve {
+    /// Slow obstacles with wide gaps that stay forgiving
+    pub fn easy() -> Self {
+        Self {
+            start: Difficulty {
+                scroll_speed: 0.8,
+                rect_interval: 1300,
+                min_width: 2,
+                max_width: 7,
+                min_height: 3,
+                max_height: 10,
+                min_gap: 8,
+                max_gap: 10,
+            },
+            limit: Difficulty {
+                scroll_speed: 1.5,
+                rect_interval: 900,
+                min_width: 2,
+                max_width: 9,
+                min_height: 3,
+                max_height: 12,
+                min_gap: 6,
+                max_gap: 8,
+            },
+            rate: 0.15,
+        }
+    }
+
+    /// The library's original feel at level 1
+    pub fn normal() -> Self {
+        Self {
+            start: Difficulty {
+                scroll_speed: 1.0,
+                rect_interval: RECT_INTERVAL,
+                min_width: 2,
+                max_width: 9,
+                min_height: 3,
+                max_height: 13,
+                min_gap: 6,
+                max_gap: 8,
+            },
+            limit: Difficulty {
+                scroll_speed: 2.0,
+                rect_interval: 700,
+                min_width: 3,
+                max_width: 12,
+                min_height: 5,
+                max_height: 15,
+                min_gap: MIN_PIPE_GAP,
+                max_gap: 5,
+            },
+            rate: 0.2,
+        }
+    }
+
+    /// Fast, wide obstacles with tight gaps from the start
+    pub fn hard() -> Self {
+        Self {
+            start: Difficulty {
+                scroll_speed: 1.3,
+                rect_interval: 800,
+                min_width: 3,
+                max_width: 11,
+                min_height: 4,
+                max_height: 14,
+                min_gap: 5,
+                max_gap: 6,
+            },
+            limit: Difficulty {
+                scroll_speed: 2.5,
+                rect_interval: 500,
+                min_width: 4,
+                max_width: 14,
+                min_height: 6,
+                max_height: 16,
+                min_gap: MIN_PIPE_GAP,
+                max_gap: MIN_PIPE_GAP,
+            },
+            rate: 0.3,
+        }
+    }
+
+    /// Returns the difficulty of `level`, counting from 1
+    pub fn at_level(&self, level: u16) -> Difficulty {
+        let progress = 1.0 - (1.0 - self.rate).powi(level.saturating_sub(1) as i32);
+        self.start.lerp(&self.limit, progress)
+    }
+
+    /// Checks that every level of the curve can be played
+    ///
+    /// Levels lie between `start` and `limit`, so checking both ends is
+    /// enough.
+    ///
+    /// # Errors
+    /// Returns the first violated invariant.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.start.validate()?;
+        self.limit.validate()?;
+        if !(self.rate > 0.0 && self.rate <= 1.0) {
+            return Err(ConfigError::DifficultyRate);
+        }
+        Ok(())
+    }
+}
+
+/// A built-in [`DifficultyCurve`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Preset {
+    /// Returns the curve of this preset
+    pub fn curve(self) -> DifficultyCurve {
+        match self {
+            Preset::Easy => DifficultyCurve::easy(),
+            Preset::Normal => DifficultyCurve::normal(),
+            Preset::Hard => DifficultyCurve::hard(),
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = UnknownPreset;
+
+    /// Parses `easy`, `normal` or `hard`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Preset::Easy),
+            "normal" => Ok(Preset::Normal),
+            "hard" => Ok(Preset::Hard),
+            _ => Err(UnknownPreset),
+        }
+    }
+}
+
+/// Error returned when a preset name is not recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownPreset;
+
+impl fmt::Display for UnknownPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown difficulty, expected easy, normal or hard")
+    }
+}
+
+impl std::error::Error for UnknownPreset {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in [Preset::Easy, Preset::Normal, Preset::Hard] {
+            assert_eq!(preset.curve().validate(), Ok(()), "{:?}", preset);
+        }
+        assert_eq!("Hard".parse(), Ok(Preset::Hard));
+        assert_eq!("brutal".parse::<Preset>(), Err(UnknownPreset));
+    }
+
+    #[test]
+    fn test_curve_ramps_towards_limit() {
+        let curve = DifficultyCurve::normal();
+        assert_eq!(curve.at_level(1), curve.start);
+
+        let mut previous = curve.at_level(1);
+        for level in 2..40 {
+            let current = curve.at_level(level);
+            assert!(current.scroll_speed > previous.scroll_speed);
+            assert!(current.scroll_speed <= curve.limit.scroll_speed);
+            assert!(current.rect_interval <= previous.rect_interval);
+            assert!(current.max_gap <= previous.max_gap);
+            assert!(current.validate().is_ok());
+            previous = current;
+        }
+        assert_eq!(curve.at_level(u16::MAX), curve.limit);
+    }
+
+    #[test]
+    fn test_invalid_curves() {
+        let base = DifficultyCurve::normal();
+        let start = base.start;
+
+        let cases = [
+            (DifficultyCurve { rate: 0.0, ..base }, ConfigError::DifficultyRate),
+            (DifficultyCurve { rate: 1.5, ..base }, ConfigError::DifficultyRate),
+            (DifficultyCurve { start: Difficulty { scroll_speed: 0.0, ..start }, ..base }, ConfigError::ScrollSpeed),
+            (DifficultyCurve { start: Difficulty { rect_interval: 0, ..start }, ..base }, ConfigError::RectInterval),
+            (DifficultyCurve { start: Difficulty { min_width: 10, ..start }, ..base }, ConfigError::ObstacleWidth),
+            (DifficultyCurve { limit: Difficulty { max_width: 100, ..base.limit }, ..base }, ConfigError::ObstacleWidth),
+            (DifficultyCurve { start: Difficulty { min_height: 0, ..start }, ..base }, ConfigError::ObstacleHeight),
+            (DifficultyCurve { limit: Difficulty { min_gap: 3, ..base.limit }, ..base }, ConfigError::PipeGap),
+        ];
+
+        for (curve, expected) in cases {
+            assert_eq!(curve.validate(), Err(expected));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::Result;
+
+use crate::render::{Color, Renderer};
+
+/// Character and color stored in one cell of the grid
+type Glyph = (char, Color);
+
+/// Double-buffered cell grid in front of another renderer
+///
+/// Drawing calls fill the next frame in memory. On
+/// [`present`](Renderer::present) the frame is compared with the one
+/// already on screen and only the cells that changed are sent to the
+/// backend, so a static border or HUD costs nothing after the first frame.
+///
+/// # Example
+/// ```
+/// use game_lib::frame::FrameBuffer;
+/// # use game_lib::render::{Color, Renderer};
+/// # struct Null;
+/// # impl Renderer for Null {
+/// #     fn size(&self) -> std::io::Result<(u16, u16)> { Ok((20, 10)) }
+/// #     fn clear(&mut self) -> std::io::Result<()> { Ok(()) }
+/// #     fn draw_cell(&mut self, _: u16, _: u16, _: char, _: Color) -> std::io::Result<()> { Ok(()) }
+/// #     fn present(&mut self) -> std::io::Result<()> { Ok(()) }
+/// # }
+///
+/// let mut frame = FrameBuffer::new(Null).unwrap();
+/// frame.draw_text(2, 1, "Score: 0", Color::Green).unwrap();
+/// frame.present().unwrap();
+/// assert_eq!(frame.changed_cells(), 8);
+///
+/// // Redrawing the same frame sends nothing
+/// frame.clear().unwrap();
+/// frame.draw_text(2, 1, "Score: 0", Color::Green).unwrap();
+/// frame.present().unwrap();
+/// assert_eq!(frame.changed_cells(), 0);
+/// ```
+pub struct FrameBuffer<R: Renderer> {
+    backend: R,
+    size: (u16, u16),
+    current: Vec<Option<Glyph>>,
+    next: Vec<Option<Glyph>>,
+    full_redraw: bool,
+    changed: usize,
+}
+
+impl<R: Renderer> FrameBuffer<R> {
+    /// Creates a frame buffer matching the backend's size
+    pub fn new(backend: R) -> Result<Self> {
+        let size = backend.size()?;
+        let cells = size.0 as usize * size.1 as usize;
+        Ok(Self {
+            backend,
+            size,
+            current: vec![None; cells],
+            next: vec![None; cells],
+            full_redraw: true,
+            changed: 0,
+        })
+    }
+
+    /// Returns the renderer frames are presented to
+    pub fn backend(&self) -> &R {
+        &self.backend
+    }
+
+    /// Returns the renderer frames are presented to
+    pub fn backend_mut(&mut self) -> &mut R {
+        &mut self.backend
+    }
+
+    /// Returns the number of cells sent to the backend by the last present
+    pub fn changed_cells(&self) -> usize {
+        self.changed
+    }
+
+    /// Changes the grid size and forces the next present to redraw everything
+    pub fn resize(&mut self, size: (u16, u16)) {
+        let cells = size.0 as usize * size.1 as usize;
+        self.size = size;
+        self.current = vec![None; cells];
+        self.next = vec![None; cells];
+        self.full_redraw = true;
+    }
+
+    /// Forces the next present to redraw every cell
+    pub fn invalidate(&mut self) {
+        self.full_redraw = true;
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.size.0 && y < self.size.1 {
+            Some(y as usize * self.size.0 as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl<R: Renderer> Renderer for FrameBuffer<R> {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.next.fill(None);
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, ch: char, color: Color) -> Result<()> {
+        // Cells outside the grid are clipped
+        if let Some(i) = self.index(x, y) {
+            self.next[i] = Some((ch, color));
+        }
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<()> {
+        if self.full_redraw {
+            self.backend.clear()?;
+            self.current.fill(None);
+            self.full_redraw = false;
+        }
+
+        self.changed = 0;
+        let width = self.size.0 as usize;
+        for (i, (next, current)) in self.next.iter().zip(&self.current).enumerate() {
+            if next != current {
+                let (ch, color) = next.unwrap_or((' ', Color::White));
+                let (x, y) = ((i % width) as u16, (i / width) as u16);
+                self.backend.draw_cell(x, y, ch, color)?;
+                self.changed += 1;
+            }
+        }
+        self.current.copy_from_slice(&self.next);
+
+        self.backend.present()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        cells: Vec<(u16, u16, char)>,
+        clears: usize,
+    }
+
+    impl Renderer for Recorder {
+        fn size(&self) -> Result<(u16, u16)> {
+            Ok((4, 3))
+        }
+
+        fn clear(&mut self) -> Result<()> {
+            self.clears += 1;
+            Ok(())
+        }
+
+        fn draw_cell(&mut self, x: u16, y: u16, ch: char, _color: Color) -> Result<()> {
+            self.cells.push((x, y, ch));
+            Ok(())
+        }
+
+        fn present(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_only_changed_cells_are_sent() {
+        let mut frame = FrameBuffer::new(Recorder::default()).unwrap();
+
+        frame.draw_cell(0, 0, '*', Color::Magenta).unwrap();
+        frame.draw_cell(1, 0, 'a', Color::Green).unwrap();
+        frame.present().unwrap();
+        assert_eq!(frame.changed_cells(), 2);
+        assert_eq!(frame.backend().clears, 1);
+
+        frame.backend_mut().cells.clear();
+        frame.clear().unwrap();
+        frame.draw_cell(0, 0, '*', Color::Magenta).unwrap();
+        frame.draw_cell(2, 1, 'b', Color::Green).unwrap();
+        frame.present().unwrap();
+
+        // 'a' is erased, 'b' is new, the border cell is untouched
+        assert_eq!(frame.backend().cells, vec![(1, 0, ' '), (2, 1, 'b')]);
+        assert_eq!(frame.backend().clears, 1);
+    }
+
+    #[test]
+    fn test_out_of_bounds_cells_are_clipped() {
+        let mut frame = FrameBuffer::new(Recorder::default()).unwrap();
+        frame.draw_cell(4, 0, 'x', Color::Red).unwrap();
+        frame.draw_cell(0, 3, 'x', Color::Red).unwrap();
+        frame.present().unwrap();
+        assert_eq!(frame.changed_cells(), 0);
+    }
+
+    #[test]
+    fn test_resize_forces_full_redraw() {
+        let mut frame = FrameBuffer::new(Recorder::default()).unwrap();
+        frame.draw_cell(0, 0, '*', Color::Magenta).unwrap();
+        frame.present().unwrap();
+
+        frame.resize((6, 2));
+        assert_eq!(frame.size().unwrap(), (6, 2));
+        frame.draw_cell(5, 1, '*', Color::Magenta).unwrap();
+        frame.present().unwrap();
+        assert_eq!(frame.backend().clears, 2);
+        assert_eq!(frame.changed_cells(), 1);
+    }
+}
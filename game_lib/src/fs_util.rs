@@ -0,0 +1,56 @@
+//! File helpers shared by the modules that save game data
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Writes `contents` to `path`, creating missing parent directories
+///
+/// The text goes to a temporary file next to `path` first, which then
+/// replaces `path` in one step, so an interrupted write never leaves a
+/// half-written file behind.
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = with_suffix(path, ".tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, path)
+}
+
+/// Appends `suffix` to the file name, e.g. `run.replay` to `run.replay.tmp`
+pub(crate) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_keeps_the_extension() {
+        assert_eq!(with_suffix(Path::new("dir/run.replay"), ".tmp"), Path::new("dir/run.replay.tmp"));
+        assert_eq!(with_suffix(Path::new("scores"), ".bak"), Path::new("scores.bak"));
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_neighbours_alone() {
+        let dir = std::env::temp_dir().join(format!("game-lib-fs-{}", std::process::id()));
+        let path = dir.join("nested").join("run.replay");
+        // A file that a swapped extension would have clobbered
+        let neighbour = dir.join("nested").join("run.tmp");
+
+        write_atomic(&path, "first").unwrap();
+        fs::write(&neighbour, "keep").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_to_string(&neighbour).unwrap(), "keep");
+        assert!(!with_suffix(&path, ".tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
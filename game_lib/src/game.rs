@@ -1,39 +1,86 @@
-use crossterm::{
-    cursor::{Hide, Show},
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
-};
-use std::io::{stdout, Result};
+use std::fmt;
+use std::time::Duration;
 
 /// Represents the current state of the game
 /// 
-/// Used to control game flow and determine what actions are allowed
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Used to control game flow and determine what actions are allowed.
+/// The states change only through [`GameEvent`]s, see
+/// [`GameSession::handle`] for the allowed transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameStatus {
-    /// Initial state before the game begins
-    Start,
+    /// Initial state before the first run
+    Title,
+    /// A run is about to begin; the world holds still until the countdown ends
+    Countdown,
     /// Game is actively being played
     Running,
-    /// Game has ended (either by collision or user action)
-    End,
+    /// A run is on hold
+    Paused,
+    /// The run has ended in a crash
+    GameOver,
+}
+
+/// Something that moves a [`GameSession`] from one [`GameStatus`] to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// Begin a new run, from any state
+    Start,
+    /// The countdown has run out
+    CountdownFinished,
+    /// Put a counting-down or running game on hold
+    Pause,
+    /// Continue a paused run, after a fresh countdown
+    Resume,
+    /// The bird hit something
+    Crash,
+    /// Leave a paused or finished run for the title
+    ToTitle,
 }
 
-/// Manages the game session state and terminal setup
+/// An event that is not allowed in the current state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionError {
+    pub status: GameStatus,
+    pub event: GameEvent,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot handle {:?} while in {:?}", self.event, self.status)
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+/// Manages the game session state
 /// 
 /// Handles:
 /// - Game status transitions
+/// - The countdown before a run starts or resumes
 /// - Level progression
-/// - Terminal initialization and cleanup
-/// - Score tracking
-#[derive(Debug)]
+/// - Score tracking and the number of obstacles passed
+/// - The RNG seed of the current run
+#[derive(Debug, Clone)]
 pub struct GameSession {
     status: GameStatus,
+    countdown: Duration,
+    remaining: Duration,
     score: u32,
+    passed: u32,
     level: u16,
+    seed: u64,
+}
+
+impl Default for GameSession {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameSession {
     /// Creates a new game session with initial state
+    ///
+    /// Runs start right away, without a countdown.
     /// 
     /// # Example
     /// ```
@@ -43,31 +90,42 @@ impl GameSession {
     /// assert_eq!(session.get_level(), 1);
     /// ```
     pub fn new() -> Self {
+        Self::with_countdown(Duration::ZERO)
+    }
+
+    /// Creates a session that counts down for `countdown` before every run
+    /// and after every pause
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::game::{GameEvent, GameSession, GameStatus};
+    /// use std::time::Duration;
+    ///
+    /// let mut session = GameSession::with_countdown(Duration::from_secs(3));
+    /// assert_eq!(session.get_status(), GameStatus::Title);
+    ///
+    /// session.handle(GameEvent::Start).unwrap();
+    /// assert_eq!(session.get_status(), GameStatus::Countdown);
+    ///
+    /// session.advance(Duration::from_secs(3));
+    /// assert!(session.is_running());
+    ///
+    /// // Only a running game can crash
+    /// session.handle(GameEvent::Crash).unwrap();
+    /// assert!(session.handle(GameEvent::Pause).is_err());
+    /// ```
+    pub fn with_countdown(countdown: Duration) -> Self {
         Self { 
-            status: GameStatus::Start, 
+            status: GameStatus::Title, 
+            countdown,
+            remaining: Duration::ZERO,
             score: 0, 
-            level: 1
+            passed: 0,
+            level: 1,
+            seed: 0,
         } 
     }
 
-    /// Sets up the terminal for game display
-    /// 
-    /// Enters alternate screen mode and hides the cursor
-    pub fn init_terminal(&self) -> Result<()> {
-        stdout().execute(EnterAlternateScreen)?;
-        stdout().execute(Hide)?;
-        Ok(())
-    }
-
-    /// Restores terminal to original state
-    /// 
-    /// Shows cursor and leaves alternate screen mode
-    pub fn cleanup_terminal(&self) -> Result<()> {
-        stdout().execute(Show)?;
-        stdout().execute(LeaveAlternateScreen)?;
-        Ok(())
-    }
-
     /// Checks if the game is currently being played
     /// 
     /// # Returns
@@ -76,16 +134,83 @@ impl GameSession {
         matches!(self.status, GameStatus::Running)
     }
 
-    /// Transitions the game to Running state
+    /// Returns the current state
+    pub fn get_status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// Applies an event and returns the new state
+    ///
+    /// | From                  | Event               | To                    |
+    /// |-----------------------|---------------------|-----------------------|
+    /// | any                   | `Start`             | `Countdown`/`Running` |
+    /// | `Countdown`           | `CountdownFinished` | `Running`             |
+    /// | `Countdown`/`Running` | `Pause`             | `Paused`              |
+    /// | `Paused`              | `Resume`            | `Countdown`/`Running` |
+    /// | `Running`             | `Crash`             | `GameOver`            |
+    /// | `Paused`/`GameOver`   | `ToTitle`           | `Title`               |
+    ///
+    /// `Countdown` is skipped when the session has no countdown. `Start`
+    /// also resets the score and level.
+    ///
+    /// # Errors
+    /// Returns a [`TransitionError`] and leaves the state alone if the event
+    /// is not allowed in the current state.
+    pub fn handle(&mut self, event: GameEvent) -> Result<GameStatus, TransitionError> {
+        use GameStatus::*;
+        let status = match (self.status, event) {
+            (_, GameEvent::Start) => {
+                self.score = 0;
+                self.passed = 0;
+                self.level = 1;
+                self.begin_countdown()
+            }
+            (Countdown, GameEvent::CountdownFinished) => Running,
+            (Countdown | Running, GameEvent::Pause) => Paused,
+            (Paused, GameEvent::Resume) => self.begin_countdown(),
+            (Running, GameEvent::Crash) => GameOver,
+            (Paused | GameOver, GameEvent::ToTitle) => Title,
+            (status, event) => return Err(TransitionError { status, event }),
+        };
+        self.status = status;
+        Ok(status)
+    }
+
+    fn begin_countdown(&mut self) -> GameStatus {
+        self.remaining = self.countdown;
+        if self.countdown.is_zero() {
+            GameStatus::Running
+        } else {
+            GameStatus::Countdown
+        }
+    }
+
+    /// Lets time pass, finishing the countdown once it runs out
+    ///
+    /// Does nothing outside of [`GameStatus::Countdown`].
+    pub fn advance(&mut self, elapsed: Duration) {
+        if self.status != GameStatus::Countdown {
+            return;
+        }
+        self.remaining = self.remaining.saturating_sub(elapsed);
+        if self.remaining.is_zero() {
+            self.status = GameStatus::Running;
+        }
+    }
+
+    /// Returns how much of the countdown is left
+    pub fn get_countdown(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Starts a new run, see [`GameEvent::Start`]
     pub fn start(&mut self) {
-        self.status = GameStatus::Running;
-        self.score = 0;
-        self.level = 1;
+        let _ = self.handle(GameEvent::Start);
     }
 
-    /// Transitions the game to End state
+    /// Ends a running game, see [`GameEvent::Crash`]
     pub fn end(&mut self) {
-        self.status = GameStatus::End;
+        let _ = self.handle(GameEvent::Crash);
     }
 
     /// Increases the current level by 1
@@ -101,9 +226,33 @@ impl GameSession {
         self.score
     }
 
+    /// Records an obstacle the bird got past, worth `points`
+    pub fn pass_obstacle(&mut self, points: u32) {
+        self.passed += 1;
+        self.score = self.score.saturating_add(points);
+    }
+
+    /// Returns how many obstacles the bird has passed this run
+    pub fn get_passed(&self) -> u32 {
+        self.passed
+    }
+
     pub fn get_level(&self) -> u16 {
         self.level
     }
+
+    /// Records the seed that generated the current run's obstacles
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the seed of the current run
+    ///
+    /// Passing it back to [`Animation::restart_with_seed`](crate::animation::Animation::restart_with_seed)
+    /// replays the same obstacles.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
 }
 
 #[cfg(test)]
@@ -127,6 +276,34 @@ mod tests {
         
         session.end();
         assert!(!session.is_running());
+        assert_eq!(session.get_status(), GameStatus::GameOver);
+    }
+
+    #[test]
+    fn test_pause_and_resume_count_down() {
+        let mut session = GameSession::with_countdown(Duration::from_secs(3));
+        session.start();
+        session.advance(Duration::from_secs(1));
+        assert_eq!(session.get_status(), GameStatus::Countdown);
+        assert_eq!(session.get_countdown(), Duration::from_secs(2));
+
+        assert_eq!(session.handle(GameEvent::CountdownFinished), Ok(GameStatus::Running));
+        session.increase_score();
+        assert_eq!(session.handle(GameEvent::Pause), Ok(GameStatus::Paused));
+        // Time stands still while paused
+        session.advance(Duration::from_secs(10));
+        assert_eq!(session.get_status(), GameStatus::Paused);
+
+        // Resuming counts down again but keeps the run
+        assert_eq!(session.handle(GameEvent::Resume), Ok(GameStatus::Countdown));
+        assert_eq!(session.get_countdown(), Duration::from_secs(3));
+        assert_eq!(session.get_score(), 1);
+
+        // The title is only reachable from a run on hold or over
+        let err = session.handle(GameEvent::ToTitle).unwrap_err();
+        assert_eq!(err.status, GameStatus::Countdown);
+        session.handle(GameEvent::Pause).unwrap();
+        assert_eq!(session.handle(GameEvent::ToTitle), Ok(GameStatus::Title));
     }
 
     #[test]
@@ -143,4 +320,19 @@ mod tests {
         }
         assert_eq!(session.get_level(), 7);
     }
+
+    #[test]
+    fn test_passing_obstacles() {
+        let mut session = GameSession::new();
+        session.start();
+
+        session.pass_obstacle(1);
+        session.pass_obstacle(5);
+        assert_eq!(session.get_passed(), 2);
+        assert_eq!(session.get_score(), 6);
+
+        // A new run starts from nothing
+        session.start();
+        assert_eq!((session.get_passed(), session.get_score()), (0, 0));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+
+/// Most simulation steps run in one tick before the loop stops catching up
+///
+/// When rendering stalls for longer than this many steps, the backlog is
+/// dropped instead of fast-forwarding the game in a burst.
+pub const MAX_CATCH_UP_STEPS: u32 = 5;
+
+/// What the caller should do on one pass through the loop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    /// Number of fixed simulation steps to run
+    pub steps: u32,
+    /// Whether a frame is due
+    pub render: bool,
+    /// How far time has moved into the next step, from 0.0 to 1.0
+    ///
+    /// Renderers can use it to interpolate between the previous and the
+    /// current simulation state, see
+    /// [`draw_scene_at`](crate::scene::draw_scene_at).
+    pub alpha: f32,
+}
+
+/// Measured rendering performance
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Frames rendered per second, measured over the last full second
+    pub fps: f32,
+    /// Time between the last two rendered frames
+    pub frame_time: Duration,
+    /// Simulation steps skipped because the loop fell too far behind
+    pub dropped_steps: u64,
+}
+
+/// Fixed-timestep driver for the game
+///
+/// Elapsed time is collected in an accumulator and paid out in steps of a
+/// fixed length, so the simulation advances at the same rate no matter how
+/// long drawing takes. Frames are scheduled separately at their own rate.
+///
+/// # Example
+/// ```
+/// use game_lib::{clock::ManualClock, game_loop::GameLoop};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let mut game_loop = GameLoop::with_clock(Duration::from_millis(40), Duration::from_millis(20), clock.clone());
+///
+/// clock.advance(Duration::from_millis(100));
+/// let tick = game_loop.tick();
+/// assert_eq!(tick.steps, 2);
+/// assert_eq!(tick.alpha, 0.5);
+/// ```
+pub struct GameLoop<C: Clock = SystemClock> {
+    clock: C,
+    step: Duration,
+    render_interval: Duration,
+    accumulator: Duration,
+    last_time: Duration,
+    next_render: Duration,
+    last_render: Option<Duration>,
+    window_start: Duration,
+    window_frames: u32,
+    stats: FrameStats,
+}
+
+impl GameLoop {
+    /// Creates a loop driven by the system clock
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn new(step: Duration, render_interval: Duration) -> Self {
+        Self::with_clock(step, render_interval, SystemClock::new())
+    }
+}
+
+impl<C: Clock> GameLoop<C> {
+    /// Creates a loop driven by the given clock
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn with_clock(step: Duration, render_interval: Duration, clock: C) -> Self {
+        assert!(!step.is_zero(), "game loop step should be positive");
+        let now = clock.now();
+        Self {
+            clock,
+            step,
+            render_interval,
+            accumulator: Duration::ZERO,
+            last_time: now,
+            next_render: now,
+            last_render: None,
+            window_start: now,
+            window_frames: 0,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Collects the time elapsed since the last tick
+    ///
+    /// Returns how many steps to simulate and whether to draw a frame.
+    pub fn tick(&mut self) -> Tick {
+        let now = self.clock.now();
+        self.accumulator += now.saturating_sub(self.last_time);
+        self.last_time = now;
+
+        let due = self.accumulator.as_nanos() / self.step.as_nanos();
+        self.accumulator -= self.step * due as u32;
+        let steps = due.min(MAX_CATCH_UP_STEPS as u128) as u32;
+        self.stats.dropped_steps += (due - steps as u128) as u64;
+
+        let render = now >= self.next_render;
+        if render {
+            self.record_frame(now);
+        }
+
+        Tick {
+            steps,
+            render,
+            alpha: self.accumulator.as_secs_f32() / self.step.as_secs_f32(),
+        }
+    }
+
+    /// Returns how long the caller can sleep before the next step or frame is due
+    pub fn time_until_next(&self) -> Duration {
+        let elapsed = self.clock.now().saturating_sub(self.last_time);
+        let next_step = (self.step - self.accumulator).saturating_sub(elapsed);
+        let next_frame = self.next_render.saturating_sub(self.clock.now());
+        next_step.min(next_frame)
+    }
+
+    /// Changes the length of a simulation step, e.g. to speed the game up
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn set_step(&mut self, step: Duration) {
+        assert!(!step.is_zero(), "game loop step should be positive");
+        self.step = step;
+        self.accumulator = self.accumulator.min(step);
+    }
+
+    /// Returns the length of a simulation step
+    pub fn get_step(&self) -> Duration {
+        self.step
+    }
+
+    /// Returns the measured frame rate and frame time
+    pub fn get_stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Returns the clock driving this loop
+    pub fn get_clock(&self) -> &C {
+        &self.clock
+    }
+
+    fn record_frame(&mut self, now: Duration) {
+        if let Some(last) = self.last_render {
+            self.stats.frame_time = now - last;
+            self.window_frames += 1;
+        }
+        self.last_render = Some(now);
+
+        // Keep the schedule unless we are more than a whole frame late
+        self.next_render += self.render_interval;
+        if self.next_render <= now {
+            self.next_render = now + self.render_interval;
+        }
+
+        let window = now - self.window_start;
+        if window >= Duration::from_secs(1) {
+            self.stats.fps = self.window_frames as f32 / window.as_secs_f32();
+            self.window_start = now;
+            self.window_frames = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn game_loop(step_ms: u64, render_ms: u64) -> (GameLoop<ManualClock>, ManualClock) {
+        let clock = ManualClock::new();
+        let game_loop = GameLoop::with_clock(
+            Duration::from_millis(step_ms),
+            Duration::from_millis(render_ms),
+            clock.clone(),
+        );
+        (game_loop, clock)
+    }
+
+    #[test]
+    fn test_accumulator_pays_out_fixed_steps() {
+        let (mut game_loop, clock) = game_loop(40, 40);
+
+        clock.advance(Duration::from_millis(30));
+        assert_eq!(game_loop.tick().steps, 0);
+
+        // 30ms left over from before plus 70ms now
+        clock.advance(Duration::from_millis(70));
+        let tick = game_loop.tick();
+        assert_eq!(tick.steps, 2);
+        assert_eq!(tick.alpha, 0.5);
+    }
+
+    #[test]
+    fn test_slow_frames_do_not_change_simulation_rate() {
+        let (mut fast, fast_clock) = game_loop(10, 10);
+        let (mut slow, slow_clock) = game_loop(10, 10);
+
+        let mut fast_steps = 0;
+        for _ in 0..50 {
+            fast_clock.advance(Duration::from_millis(10));
+            fast_steps += fast.tick().steps;
+        }
+        let mut slow_steps = 0;
+        for _ in 0..10 {
+            slow_clock.advance(Duration::from_millis(50));
+            slow_steps += slow.tick().steps;
+        }
+
+        assert_eq!(fast_steps, 50);
+        assert_eq!(slow_steps, 50);
+    }
+
+    #[test]
+    fn test_catch_up_is_capped() {
+        let (mut game_loop, clock) = game_loop(40, 40);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(game_loop.tick().steps, MAX_CATCH_UP_STEPS);
+        assert_eq!(game_loop.get_stats().dropped_steps, 20);
+
+        clock.advance(Duration::from_millis(40));
+        assert_eq!(game_loop.tick().steps, 1);
+    }
+
+    #[test]
+    fn test_render_rate_is_independent() {
+        let (mut game_loop, clock) = game_loop(10, 50);
+
+        let mut steps = 0;
+        let mut frames = 0;
+        for _ in 0..100 {
+            let tick = game_loop.tick();
+            steps += tick.steps;
+            frames += tick.render as u32;
+            clock.advance(Duration::from_millis(10));
+        }
+
+        assert_eq!(steps, 99);
+        assert_eq!(frames, 20);
+    }
+
+    #[test]
+    fn test_measures_fps_and_frame_time() {
+        let (mut game_loop, clock) = game_loop(10, 25);
+
+        for _ in 0..=40 {
+            game_loop.tick();
+            clock.advance(Duration::from_millis(25));
+        }
+
+        let stats = game_loop.get_stats();
+        assert_eq!(stats.frame_time, Duration::from_millis(25));
+        assert_eq!(stats.fps, 40.0);
+    }
+
+    #[test]
+    fn test_time_until_next() {
+        let (mut game_loop, clock) = game_loop(40, 100);
+        game_loop.tick();
+
+        clock.advance(Duration::from_millis(15));
+        assert_eq!(game_loop.time_until_next(), Duration::from_millis(25));
+
+        game_loop.set_step(Duration::from_millis(20));
+        assert_eq!(game_loop.time_until_next(), Duration::from_millis(5));
+    }
+}
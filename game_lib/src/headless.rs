@@ -0,0 +1,112 @@
+use std::io::Result;
+
+use crate::render::{Color, Renderer};
+
+/// In-memory renderer that keeps frames as text
+///
+/// Useful for tests and tools that need to look at a frame without a
+/// terminal. Each presented frame is available as one `String` per row.
+///
+/// # Example
+/// ```
+/// use game_lib::headless::TextRenderer;
+/// use game_lib::render::{Color, Renderer};
+///
+/// let mut renderer = TextRenderer::new((6, 2));
+/// renderer.draw_text(1, 0, "hi", Color::Green).unwrap();
+/// renderer.present().unwrap();
+/// assert_eq!(renderer.lines(), vec![" hi   ", "      "]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextRenderer {
+    size: (u16, u16),
+    drawing: Vec<Option<(char, Color)>>,
+    presented: Vec<Option<(char, Color)>>,
+}
+
+impl TextRenderer {
+    /// Creates a blank renderer of the given (width, height)
+    pub fn new(size: (u16, u16)) -> Self {
+        let cells = size.0 as usize * size.1 as usize;
+        Self {
+            size,
+            drawing: vec![None; cells],
+            presented: vec![None; cells],
+        }
+    }
+
+    /// Returns the last presented frame, one string per row
+    ///
+    /// Cells that were never drawn are spaces.
+    pub fn lines(&self) -> Vec<String> {
+        self.presented
+            .chunks(self.size.0.max(1) as usize)
+            .take(self.size.1 as usize)
+            .map(|row| row.iter().map(|cell| cell.map_or(' ', |(ch, _)| ch)).collect())
+            .collect()
+    }
+
+    /// Returns the character and color of a cell in the last presented frame
+    pub fn cell(&self, x: u16, y: u16) -> Option<(char, Color)> {
+        self.index(x, y).and_then(|i| self.presented[i])
+    }
+
+    /// Changes the size, discarding everything drawn so far
+    pub fn resize(&mut self, size: (u16, u16)) {
+        *self = Self::new(size);
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.size.0 && y < self.size.1 {
+            Some(y as usize * self.size.0 as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl Renderer for TextRenderer {
+    fn size(&self) -> Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.drawing.fill(None);
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, ch: char, color: Color) -> Result<()> {
+        if let Some(i) = self.index(x, y) {
+            self.drawing[i] = Some((ch, color));
+        }
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<()> {
+        self.presented.copy_from_slice(&self.drawing);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_reflect_presented_frame() {
+        let mut renderer = TextRenderer::new((3, 2));
+        renderer.draw_cell(0, 0, 'a', Color::Red).unwrap();
+        assert_eq!(renderer.lines(), vec!["   ", "   "]);
+
+        renderer.present().unwrap();
+        assert_eq!(renderer.lines(), vec!["a  ", "   "]);
+        assert_eq!(renderer.cell(0, 0), Some(('a', Color::Red)));
+        assert_eq!(renderer.cell(1, 0), None);
+
+        renderer.clear().unwrap();
+        renderer.draw_cell(2, 1, 'b', Color::Blue).unwrap();
+        renderer.draw_cell(3, 1, 'c', Color::Blue).unwrap();
+        renderer.present().unwrap();
+        assert_eq!(renderer.lines(), vec!["   ", "  b"]);
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::fs_util;
+
+/// Number of entries a table keeps by default
+pub const DEFAULT_CAPACITY: usize = 10;
+
+/// Longest name stored with a score, in characters
+pub const MAX_NAME_LENGTH: usize = 12;
+
+/// Name used when a player leaves the name empty
+const ANONYMOUS: &str = "???";
+
+/// First line of a saved table
+const HEADER: &str = "# score\tlevel\tdate\tseed\tname";
+
+/// One finished run in the high-score table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    pub level: u16,
+    /// When the run ended, in seconds since the Unix epoch
+    pub date: u64,
+    /// Seed of the run, to replay it
+    pub seed: u64,
+}
+
+impl HighScore {
+    /// Creates an entry dated now
+    ///
+    /// The name is cleaned up for storage: control characters become spaces,
+    /// surrounding whitespace is trimmed and it is cut to
+    /// [`MAX_NAME_LENGTH`] characters.
+    pub fn new(name: &str, score: u32, level: u16, seed: u64) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        Self {
+            name: clean_name(name),
+            score,
+            level,
+            date,
+            seed,
+        }
+    }
+
+    /// Returns the entry's date as `YYYY-MM-DD` (UTC)
+    pub fn get_date_text(&self) -> String {
+        format_date(self.date)
+    }
+}
+
+/// The best runs, highest score first
+///
+/// Tables are saved as plain text, one tab-separated entry per line, so they
+/// survive between runs of the game. Use [`default_path`] for the usual
+/// location.
+///
+/// # Example
+/// ```
+/// use game_lib::highscore::{HighScore, HighScores};
+///
+/// let mut table = HighScores::new(2);
+/// assert_eq!(table.insert(HighScore::new("ann", 10, 2, 1)), Some(0));
+/// assert_eq!(table.insert(HighScore::new("bob", 30, 4, 2)), Some(0));
+///
+/// // A full table only takes scores that beat its lowest entry
+/// assert!(!table.qualifies(5));
+/// assert_eq!(table.insert(HighScore::new("cy", 20, 3, 3)), Some(1));
+/// assert_eq!(table.get_entries()[1].name, "cy");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+    capacity: usize,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl HighScores {
+    /// Creates an empty table that keeps the best `capacity` runs
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns `true` if a run with `score` would make it into the table
+    ///
+    /// Runs that scored nothing never qualify.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < self.capacity
+                || self.entries.last().is_some_and(|lowest| score > lowest.score))
+    }
+
+    /// Adds an entry in score order and returns its rank, counting from 0
+    ///
+    /// On equal scores the older entry stays ahead. Returns `None` if the
+    /// entry did not make it into the table.
+    ///
+    /// The name is cleaned up as in [`HighScore::new`], so entries built by
+    /// hand are stored safely too.
+    pub fn insert(&mut self, mut entry: HighScore) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        entry.name = clean_name(&entry.name);
+        let rank = self.entries.partition_point(|other| other.score >= entry.score);
+        self.entries.insert(rank, entry);
+        self.entries.truncate(self.capacity);
+        Some(rank)
+    }
+
+    /// Returns the entries, highest score first
+    pub fn get_entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// Returns how many entries the table keeps
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Reads a table saved with [`HighScores::save`]
+    ///
+    /// A missing file is an empty table.
+    pub fn load(path: &Path, capacity: usize) -> Result<Self, HighScoreError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text, capacity),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::new(capacity)),
+            Err(err) => Err(HighScoreError::Io(err)),
+        }
+    }
+
+    /// Writes the table, creating missing parent directories
+    ///
+    /// The file is replaced in one step, so an interrupted save never leaves
+    /// a half-written table behind.
+    pub fn save(&self, path: &Path) -> Result<(), HighScoreError> {
+        fs_util::write_atomic(path, &self.to_text()).map_err(HighScoreError::Io)
+    }
+
+    /// Parses a saved table, keeping its best `capacity` entries
+    pub fn parse(text: &str, capacity: usize) -> Result<Self, HighScoreError> {
+        let mut table = Self::new(capacity);
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).ok_or(HighScoreError::Syntax { line: index + 1 })?;
+            table.insert(entry);
+        }
+        Ok(table)
+    }
+
+    /// Returns the table in its saved form
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for entry in &self.entries {
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                entry.score, entry.level, entry.date, entry.seed, entry.name
+            ));
+        }
+        text
+    }
+}
+
+fn parse_entry(line: &str) -> Option<HighScore> {
+    let mut fields = line.splitn(5, '\t');
+    Some(HighScore {
+        score: fields.next()?.parse().ok()?,
+        level: fields.next()?.parse().ok()?,
+        date: fields.next()?.parse().ok()?,
+        seed: fields.next()?.parse().ok()?,
+        name: fields.next()?.to_string(),
+    })
+}
+
+fn clean_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(MAX_NAME_LENGTH)
+        .collect();
+    let name = name.trim_end();
+    if name.is_empty() {
+        ANONYMOUS.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the usual place to keep `app`'s high scores
+///
+/// This is `highscores.tsv` in the `app` directory under the XDG data
+/// directory: `$XDG_DATA_HOME`, or `~/.local/share` when that is not set.
+/// Returns `None` if neither variable is available.
+pub fn default_path(app: &str) -> Option<PathBuf> {
+    data_path(app, |key| std::env::var_os(key).map(PathBuf::from))
+}
+
+/// Moves an unreadable high-score file out of the way and returns where
+/// it went
+///
+/// The file is renamed to `.bak`, so a new table can be saved without
+/// losing the old entries; they can still be fixed by hand. Earlier backups
+/// are kept: if `.bak` is taken, the file becomes `.1.bak`, `.2.bak` and so on.
+pub fn set_aside(path: &Path) -> io::Result<PathBuf> {
+    let backup = (0..)
+        .map(|count| match count {
+            0 => path.with_extension("bak"),
+            _ => path.with_extension(format!("{}.bak", count)),
+        })
+        .find(|backup| !backup.exists())
+        .expect("backup numbers never run out");
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+/// Resolves the high-score file from environment variables looked up with `var`
+pub fn data_path<F>(app: &str, var: F) -> Option<PathBuf>
+where
+    F: Fn(&str) -> Option<PathBuf>,
+{
+    // Relative values are invalid per the XDG spec and are ignored
+    let data_home = var("XDG_DATA_HOME")
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| var("HOME").map(|home| home.join(".local").join("share")))?;
+    Some(data_home.join(app).join("highscores.tsv"))
+}
+
+/// Formats seconds since the Unix epoch as a `YYYY-MM-DD` date (UTC)
+pub fn format_date(secs: u64) -> String {
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Reasons a high-score table could not be read or written
+#[derive(Debug)]
+pub enum HighScoreError {
+    Io(io::Error),
+    /// A line that is not a valid entry
+    Syntax { line: usize },
+}
+
+impl fmt::Display for HighScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HighScoreError::Io(err) => write!(f, "cannot access high scores: {}", err),
+            HighScoreError::Syntax { line } => write!(f, "line {}: invalid high-score entry", line),
+        }
+    }
+}
+
+impl std::error::Error for HighScoreError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: u32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            level: 1,
+            date: 0,
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn test_table_keeps_best_in_order() {
+        let mut table = HighScores::new(3);
+        for (name, score) in [("a", 5), ("b", 9), ("c", 5), ("d", 1), ("e", 7)] {
+            table.insert(entry(name, score));
+        }
+
+        let names: Vec<&str> = table.get_entries().iter().map(|e| e.name.as_str()).collect();
+        // The older 5 stays ahead of the newer one, which falls off
+        assert_eq!(names, ["b", "e", "a"]);
+        assert!(!table.qualifies(0));
+        assert!(!table.qualifies(5));
+        assert!(table.qualifies(6));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut table = HighScores::new(5);
+        table.insert(HighScore {
+            name: "Ann Lee".to_string(),
+            score: 42,
+            level: 3,
+            date: 1_700_000_000,
+            seed: u64::MAX,
+        });
+        table.insert(entry("bob", 7));
+
+        assert_eq!(HighScores::parse(&table.to_text(), 5).unwrap(), table);
+        assert!(matches!(
+            HighScores::parse("# header\n\n12\tx\t0\t0\tann\n", 5),
+            Err(HighScoreError::Syntax { line: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("game-lib-scores-{}", std::process::id()));
+        let path = dir.join("nested").join("highscores.tsv");
+
+        assert_eq!(HighScores::load(&path, 5).unwrap(), HighScores::new(5));
+
+        let mut table = HighScores::new(5);
+        table.insert(entry("ann", 3));
+        table.save(&path).unwrap();
+        assert_eq!(HighScores::load(&path, 5).unwrap(), table);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_set_aside() {
+        let dir = std::env::temp_dir().join(format!("game-lib-corrupt-{}", std::process::id()));
+        let path = dir.join("highscores.tsv");
+        let text = format!("{}\n12\t2\t0\t1\tann\nnot an entry\n", HEADER);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, &text).unwrap();
+
+        assert!(matches!(HighScores::load(&path, 5), Err(HighScoreError::Syntax { line: 3 })));
+        let backup = set_aside(&path).unwrap();
+        assert_eq!(backup, dir.join("highscores.bak"));
+
+        // Saving a new table leaves the old one alone
+        let mut table = HighScores::load(&path, 5).unwrap();
+        table.insert(entry("bob", 4));
+        table.save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), text);
+        assert_eq!(HighScores::load(&path, 5).unwrap(), table);
+
+        // A second bad table does not overwrite the first backup
+        fs::write(&path, "still not an entry\n").unwrap();
+        assert_eq!(set_aside(&path).unwrap(), dir.join("highscores.1.bak"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), text);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_names_are_cleaned() {
+        assert_eq!(clean_name("  ann\tlee \n"), "ann lee");
+        assert_eq!(clean_name("a very long player name"), "a very long");
+        assert_eq!(clean_name(" \t"), ANONYMOUS);
+    }
+
+    #[test]
+    fn test_inserted_names_are_cleaned() {
+        let mut table = HighScores::new(5);
+        table.insert(HighScore {
+            name: "ann\t9\t9\nlee".to_string(),
+            ..entry("", 12)
+        });
+        assert_eq!(table.get_entries()[0].name, "ann 9 9 lee");
+
+        // The name cannot break the saved table apart
+        let text = table.to_text();
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(HighScores::parse(&text, 5).unwrap(), table);
+    }
+
+    #[test]
+    fn test_xdg_data_path() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| PathBuf::from(v))
+        };
+
+        assert_eq!(
+            data_path("game", env(&[("XDG_DATA_HOME", "/data"), ("HOME", "/home/u")])),
+            Some(PathBuf::from("/data/game/highscores.tsv"))
+        );
+        assert_eq!(
+            data_path("game", env(&[("XDG_DATA_HOME", "relative"), ("HOME", "/home/u")])),
+            Some(PathBuf::from("/home/u/.local/share/game/highscores.tsv"))
+        );
+        assert_eq!(data_path("game", env(&[])), None);
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+    }
+}
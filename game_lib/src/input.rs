@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Result};
+
+/// Something the player asked the game to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Make the bird jump
+    Jump,
+    /// Start a new run
+    Restart,
+    /// Leave the game
+    Quit,
+    /// Pause or resume the game
+    Pause,
+    /// Show or leave the high-score table
+    Leaderboard,
+    /// Play a single update of a paused replay
+    Step,
+    /// Play a replay faster, or at normal speed again
+    FastForward,
+    /// The terminal now has this many columns and rows
+    Resize(u16, u16),
+}
+
+/// A device or script that produces player actions
+///
+/// Sources are polled once per frame and must never block for longer than
+/// their own read timeout.
+pub trait InputSource {
+    /// Appends the actions that arrived since the last poll to `actions`
+    ///
+    /// # Errors
+    /// Returns the error of the underlying device. Actions collected before
+    /// the error are still appended.
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()>;
+}
+
+impl<T: InputSource + ?Sized> InputSource for Box<T> {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()> {
+        (**self).poll(actions)
+    }
+}
+
+/// Replays a fixed list of actions, keyed by poll number
+///
+/// The first poll is number 0. Useful for tests and demos.
+///
+/// # Example
+/// ```
+/// use game_lib::input::{Action, InputSource, ScriptedInput};
+///
+/// let mut script = ScriptedInput::new([(1, Action::Jump), (1, Action::Jump), (3, Action::Quit)]);
+/// let mut actions = Vec::new();
+/// script.poll(&mut actions).unwrap(); // poll 0
+/// script.poll(&mut actions).unwrap(); // poll 1
+/// assert_eq!(actions, vec![Action::Jump, Action::Jump]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    script: VecDeque<(u64, Action)>,
+    polls: u64,
+}
+
+impl ScriptedInput {
+    /// Creates a script from (poll number, action) pairs
+    pub fn new<I: IntoIterator<Item = (u64, Action)>>(script: I) -> Self {
+        let mut script: Vec<_> = script.into_iter().collect();
+        script.sort_by_key(|&(poll, _)| poll);
+        Self {
+            script: script.into(),
+            polls: 0,
+        }
+    }
+
+    /// Returns `true` once every scripted action has been delivered
+    pub fn is_finished(&self) -> bool {
+        self.script.is_empty()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()> {
+        while let Some(&(poll, action)) = self.script.front() {
+            if poll > self.polls {
+                break;
+            }
+            actions.push(action);
+            self.script.pop_front();
+        }
+        self.polls += 1;
+        Ok(())
+    }
+}
+
+/// Maps a byte from a serial controller to an action
+pub fn action_for_byte(byte: u8) -> Option<Action> {
+    match byte {
+        b'i' | b'j' => Some(Action::Jump),
+        _ => None, // Ignore other characters
+    }
+}
+
+/// Reads single-byte commands from a serial port or any other byte stream
+///
+/// Read timeouts are treated as "no input" rather than errors.
+pub struct SerialInput<R: Read> {
+    reader: R,
+    buffer: [u8; 64],
+}
+
+impl<R: Read> SerialInput<R> {
+    /// Wraps a byte stream such as an open serial port
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: [0; 64],
+        }
+    }
+}
+
+impl<R: Read> InputSource for SerialInput<R> {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()> {
+        match self.reader.read(&mut self.buffer) {
+            Ok(count) => {
+                actions.extend(self.buffer[..count].iter().copied().filter_map(action_for_byte));
+                Ok(())
+            }
+            Err(ref e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Polls several sources as one
+///
+/// Every source is polled each time, even if an earlier one fails; the
+/// first error is returned after all sources have had their turn.
+#[derive(Default)]
+pub struct MergedInput {
+    sources: Vec<Box<dyn InputSource>>,
+}
+
+impl MergedInput {
+    /// Creates a merged source from a list of sources
+    pub fn new(sources: Vec<Box<dyn InputSource>>) -> Self {
+        Self { sources }
+    }
+
+    /// Adds another source, polled after the existing ones
+    pub fn push(&mut self, source: Box<dyn InputSource>) {
+        self.sources.push(source);
+    }
+}
+
+impl InputSource for MergedInput {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()> {
+        let mut first_error = None;
+        for source in &mut self.sources {
+            if let Err(err) = source.poll(actions) {
+                first_error.get_or_insert(err);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Cursor};
+
+    #[test]
+    fn test_scripted_input() {
+        let mut script = ScriptedInput::new([(2, Action::Quit), (0, Action::Jump)]);
+        let mut actions = Vec::new();
+
+        script.poll(&mut actions).unwrap();
+        assert_eq!(actions, vec![Action::Jump]);
+        script.poll(&mut actions).unwrap();
+        assert_eq!(actions, vec![Action::Jump]);
+        assert!(!script.is_finished());
+        script.poll(&mut actions).unwrap();
+        assert_eq!(actions, vec![Action::Jump, Action::Quit]);
+        assert!(script.is_finished());
+    }
+
+    #[test]
+    fn test_serial_input_maps_bytes() {
+        let mut serial = SerialInput::new(Cursor::new(b"ixj?".to_vec()));
+        let mut actions = Vec::new();
+        serial.poll(&mut actions).unwrap();
+        assert_eq!(actions, vec![Action::Jump, Action::Jump]);
+    }
+
+    struct Failing(ErrorKind);
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> Result<usize> {
+            Err(io::Error::from(self.0))
+        }
+    }
+
+    #[test]
+    fn test_serial_timeout_is_not_an_error() {
+        let mut serial = SerialInput::new(Failing(ErrorKind::TimedOut));
+        assert!(serial.poll(&mut Vec::new()).is_ok());
+
+        let mut serial = SerialInput::new(Failing(ErrorKind::BrokenPipe));
+        assert!(serial.poll(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_merged_input_polls_every_source() {
+        let mut merged = MergedInput::new(vec![
+            Box::new(SerialInput::new(Failing(ErrorKind::BrokenPipe))),
+            Box::new(ScriptedInput::new([(0, Action::Pause)])),
+        ]);
+        merged.push(Box::new(ScriptedInput::new([(0, Action::Jump)])));
+
+        let mut actions = Vec::new();
+        assert!(merged.poll(&mut actions).is_err());
+        assert_eq!(actions, vec![Action::Pause, Action::Jump]);
+    }
+}
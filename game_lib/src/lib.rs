@@ -6,61 +6,85 @@
 //! - Physics-based bird movement with gravity and jumping
 //! - Obstacle generation and collision detection
 //! - Game state management
-//! - Terminal-based animation system
+//! - Backend-agnostic rendering, with a crossterm terminal renderer
+//!   behind the default `crossterm` feature
+//! - Pluggable clocks for deterministic simulation
+//! - A fixed-timestep game loop with a separate render rate
 //! 
 //! # Example
 //! 
 //! ```no_run
-//! use game_lib::{animation::Animation, game::GameSession};
+//! use game_lib::{animation::Animation, config::GameConfig, game::GameSession};
 //! 
+//! let screen_size = (80, 24);
 //! let mut game_session = GameSession::new();
-//! let mut animation = Animation::new(screen_size);
+//! let mut animation = Animation::new(screen_size, GameConfig::default());
+//! game_session.start();
 //! 
-//! // Game loop
+//! // Game loop, until the bird crashes
 //! while game_session.is_running() {
 //!     animation.update(&mut game_session);
 //! }
 //! ```
 
 pub mod rect;
+pub mod clock;
+pub mod collision;
+pub mod score;
+pub mod highscore;
+mod fs_util;
+pub mod game_loop;
+pub mod config;
+pub mod difficulty;
+pub mod render;
+pub mod frame;
+pub mod scene;
+pub mod sprite;
+pub mod asset;
+pub mod headless;
+pub mod replay;
+pub mod simulation;
+pub mod solvability;
+pub mod input;
+pub mod protocol;
+#[cfg(feature = "crossterm")]
+pub mod terminal;
 pub mod animation;
+pub mod autopilot;
 pub mod bird;
 pub mod game;
 
-/// Gravity constant affecting bird's vertical movement
+/// Default gravity affecting bird's vertical movement
 /// 
 /// Higher values make the bird fall faster
 pub const GRAVITY: f32 = 1.0;
 
-/// Initial upward velocity when the bird jumps
+/// Default initial upward velocity when the bird jumps
 /// 
 /// Negative value represents upward movement
 pub const JUMP_VELOCITY: f32 = -3.0;
 
-/// Time interval between physics updates in seconds
+/// Default time interval between physics updates in seconds
 /// 
 /// Used to maintain consistent game speed
 pub const UPDATE_INTERVAL: f32 = 1.0;
 
-/// Maximum falling speed of the bird
+/// Default maximum falling speed of the bird
 /// 
 /// Prevents the bird from falling too quickly
 pub const MAX_VELOCITY: f32 = 8.0;
 
-/// Time interval between rectangles are created in ms
+/// Default time interval between rectangles are created in ms
 /// 
 /// Used to maintain consistent generate speed
 pub const RECT_INTERVAL: u64 = 1000;
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::config::GameConfig;
 
     #[test]
     fn test_physics_constants() {
-        assert!(GRAVITY > 0.0, "Gravity should be positive");
-        assert!(JUMP_VELOCITY < 0.0, "Jump velocity should be negative (upward)");
-        assert!(UPDATE_INTERVAL > 0.0, "Update interval should be positive");
-        assert!(MAX_VELOCITY > GRAVITY, "Max velocity should be greater than gravity");
+        assert_eq!(GameConfig::default().validate(), Ok(()));
     }
 }
@@ -0,0 +1,424 @@
+//! Framed serial controller protocol
+//!
+//! Every message travels in a frame:
+//!
+//! | byte      | meaning                                        |
+//! |-----------|------------------------------------------------|
+//! | `0x7E`    | start of frame                                 |
+//! | type      | message type                                   |
+//! | length    | payload length, at most [`MAX_PAYLOAD`]         |
+//! | payload   | `length` bytes                                  |
+//! | checksum  | XOR of type, length and payload bytes          |
+//!
+//! A session starts with both sides sending [`Message::Hello`] with their
+//! protocol version. Input from the controller is only accepted once its
+//! hello has been received and the versions match.
+
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+
+use crate::input::{Action, InputSource};
+
+/// Version spoken by this implementation
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// First byte of every frame
+pub const START_BYTE: u8 = 0x7E;
+
+/// Largest payload a frame may carry
+pub const MAX_PAYLOAD: usize = 16;
+
+/// Analog value at or above which a hold counts as a jump
+pub const HOLD_THRESHOLD: u8 = 128;
+
+const TYPE_HELLO: u8 = 0x01;
+const TYPE_BUTTON: u8 = 0x02;
+const TYPE_HOLD: u8 = 0x03;
+
+/// Physical buttons a controller may have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Jump,
+    Restart,
+    Pause,
+    Quit,
+}
+
+impl Button {
+    fn code(self) -> u8 {
+        match self {
+            Button::Jump => 0,
+            Button::Restart => 1,
+            Button::Pause => 2,
+            Button::Quit => 3,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Button::Jump),
+            1 => Some(Button::Restart),
+            2 => Some(Button::Pause),
+            3 => Some(Button::Quit),
+            _ => None,
+        }
+    }
+
+    /// Returns the game action triggered by pressing this button
+    pub fn action(self) -> Action {
+        match self {
+            Button::Jump => Action::Jump,
+            Button::Restart => Action::Restart,
+            Button::Pause => Action::Pause,
+            Button::Quit => Action::Quit,
+        }
+    }
+}
+
+/// A decoded protocol message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Handshake carrying the sender's protocol version
+    Hello { version: u8 },
+    /// A button changed state
+    Button { button: Button, pressed: bool },
+    /// Current value of the analog hold input, 0 to 255
+    Hold { value: u8 },
+}
+
+impl Message {
+    /// Encodes the message as a complete frame
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::protocol::{Decoder, Message};
+    ///
+    /// let mut decoder = Decoder::new();
+    /// decoder.feed(&Message::Hold { value: 200 }.encode());
+    /// assert_eq!(decoder.next_message(), Some(Ok(Message::Hold { value: 200 })));
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        let (kind, payload) = match *self {
+            Message::Hello { version } => (TYPE_HELLO, vec![version]),
+            Message::Button { button, pressed } => (TYPE_BUTTON, vec![button.code(), pressed as u8]),
+            Message::Hold { value } => (TYPE_HOLD, vec![value]),
+        };
+
+        let mut frame = vec![START_BYTE, kind, payload.len() as u8];
+        frame.extend_from_slice(&payload);
+        frame.push(checksum(kind, &payload));
+        frame
+    }
+
+    fn parse(kind: u8, payload: &[u8]) -> Result<Self, FrameError> {
+        match (kind, payload) {
+            (TYPE_HELLO, &[version]) => Ok(Message::Hello { version }),
+            (TYPE_BUTTON, &[code, pressed]) => {
+                let button = Button::from_code(code).ok_or(FrameError::BadPayload(kind))?;
+                Ok(Message::Button { button, pressed: pressed != 0 })
+            }
+            (TYPE_HOLD, &[value]) => Ok(Message::Hold { value }),
+            (TYPE_HELLO | TYPE_BUTTON | TYPE_HOLD, _) => Err(FrameError::BadPayload(kind)),
+            _ => Err(FrameError::UnknownType(kind)),
+        }
+    }
+}
+
+fn checksum(kind: u8, payload: &[u8]) -> u8 {
+    payload.iter().fold(kind ^ payload.len() as u8, |sum, byte| sum ^ byte)
+}
+
+/// Why a frame was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The checksum byte did not match the frame contents
+    Checksum,
+    /// The declared payload is longer than [`MAX_PAYLOAD`]
+    TooLong(u8),
+    /// The message type is not part of the protocol
+    UnknownType(u8),
+    /// The payload does not fit the message type
+    BadPayload(u8),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Checksum => f.write_str("frame checksum mismatch"),
+            FrameError::TooLong(len) => write!(f, "frame payload of {} bytes is too long", len),
+            FrameError::UnknownType(kind) => write!(f, "unknown message type {:#04x}", kind),
+            FrameError::BadPayload(kind) => write!(f, "malformed payload for message type {:#04x}", kind),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Incremental frame decoder
+///
+/// Bytes can arrive in any chunking. Noise between frames is skipped, and
+/// after a corrupt frame the decoder resynchronizes on the next start byte.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+}
+
+impl Decoder {
+    /// Creates an empty decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends received bytes
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete message, if one is buffered
+    ///
+    /// Rejected frames are returned as errors so callers can count them;
+    /// decoding continues with the following bytes.
+    pub fn next_message(&mut self) -> Option<Result<Message, FrameError>> {
+        // Skip noise up to the next start byte
+        let start = self.buffer.iter().position(|&byte| byte == START_BYTE);
+        self.buffer.drain(..start.unwrap_or(self.buffer.len()));
+
+        if self.buffer.len() < 3 {
+            return None;
+        }
+        let (kind, len) = (self.buffer[1], self.buffer[2]);
+        if len as usize > MAX_PAYLOAD {
+            self.buffer.remove(0);
+            return Some(Err(FrameError::TooLong(len)));
+        }
+
+        let frame_len = 4 + len as usize;
+        if self.buffer.len() < frame_len {
+            return None;
+        }
+        let payload = &self.buffer[3..3 + len as usize];
+        if checksum(kind, payload) != self.buffer[frame_len - 1] {
+            // The start byte may have been noise; look for a frame after it
+            self.buffer.remove(0);
+            return Some(Err(FrameError::Checksum));
+        }
+
+        let message = Message::parse(kind, payload);
+        self.buffer.drain(..frame_len);
+        Some(message)
+    }
+}
+
+/// Input source speaking the framed protocol over a serial port or any stream
+///
+/// Sends its hello on creation and ignores input until the controller's
+/// hello arrives. Button presses become actions; the analog hold input
+/// triggers a jump each time it rises to [`HOLD_THRESHOLD`].
+pub struct FramedInput<S: Read + Write> {
+    stream: S,
+    decoder: Decoder,
+    buffer: [u8; 64],
+    handshake_done: bool,
+    holding: bool,
+    rejected_frames: u64,
+}
+
+impl<S: Read + Write> FramedInput<S> {
+    /// Wraps a stream and sends the hello frame
+    pub fn new(mut stream: S) -> io::Result<Self> {
+        stream.write_all(&Message::Hello { version: PROTOCOL_VERSION }.encode())?;
+        stream.flush()?;
+        Ok(Self {
+            stream,
+            decoder: Decoder::new(),
+            buffer: [0; 64],
+            handshake_done: false,
+            holding: false,
+            rejected_frames: 0,
+        })
+    }
+
+    /// Returns `true` once the controller's hello has been accepted
+    pub fn is_connected(&self) -> bool {
+        self.handshake_done
+    }
+
+    /// Returns the number of corrupt or unknown frames received
+    pub fn rejected_frames(&self) -> u64 {
+        self.rejected_frames
+    }
+
+    fn handle(&mut self, message: Message, actions: &mut Vec<Action>) -> io::Result<()> {
+        match message {
+            Message::Hello { version } if version == PROTOCOL_VERSION => {
+                self.handshake_done = true;
+            }
+            Message::Hello { version } => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("controller speaks protocol version {}, expected {}", version, PROTOCOL_VERSION),
+                ));
+            }
+            _ if !self.handshake_done => {}
+            Message::Button { button, pressed: true } => actions.push(button.action()),
+            Message::Button { pressed: false, .. } => {}
+            Message::Hold { value } => {
+                let holding = value >= HOLD_THRESHOLD;
+                if holding && !self.holding {
+                    actions.push(Action::Jump);
+                }
+                self.holding = holding;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Read + Write> InputSource for FramedInput<S> {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> io::Result<()> {
+        match self.stream.read(&mut self.buffer) {
+            Ok(count) => self.decoder.feed(&self.buffer[..count]),
+            Err(ref e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {}
+            Err(e) => return Err(e),
+        }
+
+        while let Some(decoded) = self.decoder.next_message() {
+            match decoded {
+                Ok(message) => self.handle(message, actions)?,
+                Err(_) => self.rejected_frames += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_round_trip() {
+        let messages = [
+            Message::Hello { version: PROTOCOL_VERSION },
+            Message::Button { button: Button::Pause, pressed: true },
+            Message::Button { button: Button::Quit, pressed: false },
+            Message::Hold { value: 0 },
+        ];
+        let mut decoder = Decoder::new();
+        for message in messages {
+            decoder.feed(&message.encode());
+        }
+        for message in messages {
+            assert_eq!(decoder.next_message(), Some(Ok(message)));
+        }
+        assert_eq!(decoder.next_message(), None);
+    }
+
+    #[test]
+    fn test_split_frames_and_noise() {
+        let frame = Message::Hold { value: 7 }.encode();
+        let mut decoder = Decoder::new();
+
+        decoder.feed(b"ij");
+        decoder.feed(&frame[..2]);
+        assert_eq!(decoder.next_message(), None);
+        decoder.feed(&frame[2..]);
+        assert_eq!(decoder.next_message(), Some(Ok(Message::Hold { value: 7 })));
+    }
+
+    #[test]
+    fn test_corrupt_frame_resynchronizes() {
+        let mut corrupt = Message::Hold { value: 7 }.encode();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+
+        let mut decoder = Decoder::new();
+        decoder.feed(&corrupt);
+        decoder.feed(&Message::Hold { value: 9 }.encode());
+
+        assert_eq!(decoder.next_message(), Some(Err(FrameError::Checksum)));
+        assert_eq!(decoder.next_message(), Some(Ok(Message::Hold { value: 9 })));
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_oversized_frames() {
+        let mut decoder = Decoder::new();
+        decoder.feed(&[START_BYTE, 0x42, 0, 0x42]);
+        assert_eq!(decoder.next_message(), Some(Err(FrameError::UnknownType(0x42))));
+
+        decoder.feed(&[START_BYTE, TYPE_BUTTON, 2, 9, 1, TYPE_BUTTON ^ 2 ^ 9 ^ 1]);
+        assert_eq!(decoder.next_message(), Some(Err(FrameError::BadPayload(TYPE_BUTTON))));
+
+        decoder.feed(&[START_BYTE, TYPE_HOLD, 200]);
+        assert_eq!(decoder.next_message(), Some(Err(FrameError::TooLong(200))));
+        assert_eq!(decoder.next_message(), None);
+    }
+
+    /// In-memory serial line: reads from a script, records what is written
+    struct Line {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Line {
+        fn new(messages: &[Message]) -> Self {
+            Self {
+                input: Cursor::new(messages.iter().flat_map(Message::encode).collect()),
+                output: Vec::new(),
+            }
+        }
+    }
+
+    impl Read for Line {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Line {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_framed_input_handshake_and_actions() {
+        let line = Line::new(&[
+            Message::Button { button: Button::Jump, pressed: true }, // before hello: ignored
+            Message::Hello { version: PROTOCOL_VERSION },
+            Message::Button { button: Button::Jump, pressed: true },
+            Message::Button { button: Button::Jump, pressed: false },
+            Message::Button { button: Button::Restart, pressed: true },
+            Message::Hold { value: 200 },
+            Message::Hold { value: 250 },
+            Message::Hold { value: 10 },
+            Message::Hold { value: 130 },
+        ]);
+        let mut input = FramedInput::new(line).unwrap();
+        assert_eq!(input.stream.output, Message::Hello { version: PROTOCOL_VERSION }.encode());
+
+        let mut actions = Vec::new();
+        while input.stream.input.position() < input.stream.input.get_ref().len() as u64 {
+            input.poll(&mut actions).unwrap();
+        }
+
+        assert!(input.is_connected());
+        assert_eq!(
+            actions,
+            vec![Action::Jump, Action::Restart, Action::Jump, Action::Jump]
+        );
+    }
+
+    #[test]
+    fn test_framed_input_rejects_other_versions() {
+        let line = Line::new(&[Message::Hello { version: PROTOCOL_VERSION + 1 }]);
+        let mut input = FramedInput::new(line).unwrap();
+        let err = input.poll(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(!input.is_connected());
+    }
+}
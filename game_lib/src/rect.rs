@@ -1,17 +1,53 @@
 use rand::Rng;
-use crossterm::style::Color;
+
+use crate::collision::Aabb;
+use crate::config::MIN_PIPE_GAP;
+use crate::difficulty::{Difficulty, MIN_OPEN_ROWS};
+use crate::render::Color;
+use crate::sprite::Sprite;
+
+/// Colors picked at random for new obstacles
+const COLORS: [Color; 5] = [
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Screen edge an obstacle grows from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    /// Grows up from the bottom edge
+    Bottom,
+    /// Hangs down from the top edge
+    Top,
+}
 
 /// Represents a rectangular obstacle in the game
 /// 
 /// Rectangles are colored blocks that the bird must avoid.
-/// They are randomly generated with varying widths and heights.
-#[derive(Debug)]
+/// They are randomly generated with varying widths and heights, and stand
+/// on the floor or hang from the ceiling depending on their [`Anchor`].
+///
+/// The horizontal position is a continuous world coordinate, so obstacles
+/// can scroll by fractions of a cell per update.
+///
+/// Each rectangle also carries the scoring state of the obstacle it belongs
+/// to: which obstacle that is, the closest the bird came to it, and whether
+/// the bird has passed it yet.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rect {
-    x: u16,
+    x: f32,
+    previous_x: f32,
     width: u16,
     height: u16,
+    anchor: Anchor,
     color: Color,
     screen_size: (u16, u16),
+    obstacle: u32,
+    clearance: Option<u16>,
+    passed: bool,
 }
 
 impl Rect {
@@ -21,49 +57,99 @@ impl Rect {
     /// * `x` - Horizontal position
     /// * `width` - Width of the rectangle
     /// * `height` - Height of the rectangle
+    /// * `anchor` - Screen edge the rectangle grows from
     /// * `color` - Color of the rectangle
     /// * `screen_size` - Terminal dimensions (width, height)
-    fn new(x: u16, width: u16, height: u16, color: Color, screen_size:(u16,u16)) -> Self {
+    fn new(x: f32, width: u16, height: u16, anchor: Anchor, color: Color, screen_size:(u16,u16)) -> Self {
         Self {
             x,
+            previous_x: x,
             width,
             height,
+            anchor,
             color,
             screen_size,
+            obstacle: 0,
+            clearance: None,
+            passed: false,
         }
     }
 
     /// Creates a new rectangle with random properties
     /// 
     /// # Arguments
-    /// * `max_width` - Maximum width of the generated rectangle
+    /// * `difficulty` - Width and height ranges of the generated rectangle
     /// * `screen_size` - Terminal dimensions (width, height)
+    /// * `rng` - Random number generator used for size and color
     /// 
     /// # Example
     /// ```
-    /// use game_lib::rect::Rect;
+    /// use game_lib::{difficulty::DifficultyCurve, rect::Rect};
+    /// use rand::{rngs::StdRng, SeedableRng};
     /// 
     /// let screen_size = (80, 24);
-    /// let rect = Rect::random(10, screen_size);
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let rect = Rect::random(&DifficultyCurve::normal().start, screen_size, &mut rng);
     /// assert!(rect.get_rightmost_x() <= screen_size.0);
     /// ```
-    pub fn random(max_width: u16, screen_size:(u16,u16)) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random<R: Rng + ?Sized>(difficulty: &Difficulty, screen_size:(u16,u16), rng: &mut R) -> Self {
+        Self::random_anchored(Anchor::Bottom, difficulty, screen_size, rng)
+    }
+
+    /// Creates a new rectangle with random properties hanging from the ceiling
+    ///
+    /// Takes the same arguments as [`Rect::random`].
+    pub fn random_ceiling<R: Rng + ?Sized>(difficulty: &Difficulty, screen_size:(u16,u16), rng: &mut R) -> Self {
+        Self::random_anchored(Anchor::Top, difficulty, screen_size, rng)
+    }
 
-        let rect_width = rng.gen_range(2..max_width);
-        let rect_height = rng.gen_range(3..screen_size.1-10);
+    fn random_anchored<R: Rng + ?Sized>(anchor: Anchor, difficulty: &Difficulty, screen_size:(u16,u16), rng: &mut R) -> Self {
+        // A validated difficulty always fits, but the screen is the hard limit
+        let rect_width = rng.gen_range(difficulty.min_width..=difficulty.max_width).min(screen_size.0);
+        // Leave the bird room to get over or under the block
+        let max_height = difficulty.max_height.min(screen_size.1.saturating_sub(MIN_OPEN_ROWS)).max(1);
+        let rect_height = rng.gen_range(difficulty.min_height.min(max_height)..=max_height);
         
-        let x = screen_size.0 - rect_width;
-        let colors = [
-            Color::Blue,
-            Color::Green,
-            Color::Yellow,
-            Color::Magenta,
-            Color::Cyan,
-        ];
-        let color = colors[rng.gen_range(0..colors.len())];
+        let x = (screen_size.0 - rect_width) as f32;
+        let color = COLORS[rng.gen_range(0..COLORS.len())];
+
+        Self::new(x, rect_width, rect_height, anchor, color, screen_size)
+    }
+
+    /// Creates a ceiling and a floor rectangle with an open gap between them
+    ///
+    /// The gap is picked from the difficulty's gap range and placed at a
+    /// random height; both rectangles share their width, position and color.
+    ///
+    /// # Arguments
+    /// * `difficulty` - Width and gap ranges of the generated pair
+    /// * `screen_size` - Terminal dimensions (width, height)
+    /// * `rng` - Random number generator used for size, gap and color
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::{difficulty::DifficultyCurve, rect::Rect};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let difficulty = DifficultyCurve::normal().start;
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let [top, bottom] = Rect::random_pair(&difficulty, (80, 24), &mut rng);
+    /// let gap = bottom.get_top_y() - top.get_bottom_y() - 1;
+    /// assert!((difficulty.min_gap..=difficulty.max_gap).contains(&gap));
+    /// ```
+    pub fn random_pair<R: Rng + ?Sized>(difficulty: &Difficulty, screen_size:(u16,u16), rng: &mut R) -> [Self; 2] {
+        let gap = rng.gen_range(difficulty.min_gap..=difficulty.max_gap);
+        let rect_width = rng.gen_range(difficulty.min_width..=difficulty.max_width).min(screen_size.0);
+        // Keep at least two rows of each pipe visible inside the border
+        let gap = gap.min(screen_size.1.saturating_sub(4));
+        let gap_top = rng.gen_range(2..=screen_size.1 - gap - 2);
+        let color = COLORS[rng.gen_range(0..COLORS.len())];
 
-        Self::new(x, rect_width, rect_height, color, screen_size)
+        let x = (screen_size.0 - rect_width) as f32;
+        [
+            Self::new(x, rect_width, gap_top, Anchor::Top, color, screen_size),
+            Self::new(x, rect_width, screen_size.1 - gap_top - gap, Anchor::Bottom, color, screen_size),
+        ]
     }
 
     /// Returns a vector of points representing the rectangle for drawing
@@ -73,54 +159,216 @@ impl Rect {
     /// - y coordinate
     /// - character to draw
     /// - color of the point
+    ///
+    /// Columns scrolled past the left edge of the screen are left out.
     pub fn draw(&self) -> Vec<(u16, u16, char, Color)> {
+        self.draw_from(self.x)
+    }
+
+    /// Returns the points of the rectangle a fraction `alpha` of the way
+    /// through the last step, see [`Rect::get_x_at`]
+    pub fn draw_at(&self, alpha: f32) -> Vec<(u16, u16, char, Color)> {
+        self.draw_from(self.get_x_at(alpha))
+    }
+
+    fn draw_from(&self, x: f32) -> Vec<(u16, u16, char, Color)> {
         let mut points = Vec::new();
+        let left = x.floor() as i32;
         
-        for y in self.screen_size.1-self.height..self.screen_size.1 {
-            for x in self.x..self.x + self.width {
-                points.push((x, y, '*', self.color));
+        for y in self.get_top_y()..=self.get_bottom_y() {
+            for x in (left..left + self.width as i32).filter(|&x| x >= 0) {
+                points.push((x as u16, y, '*', self.color));
             }
         }
         
         points
     }
     
-    /// Returns the current x position of the rectangle
-    pub fn get_x(&self) -> u16 {
+    /// Returns the points of the rectangle drawn with a texture
+    ///
+    /// The texture is tiled from the rectangle's top-left corner and scrolls
+    /// with it. Empty cells of the texture keep the plain `'*'` look, so the
+    /// whole obstacle stays visible.
+    pub fn draw_textured(&self, texture: &Sprite) -> Vec<(u16, u16, char, Color)> {
+        self.draw_textured_from(texture, self.x)
+    }
+
+    /// Returns the textured points of the rectangle a fraction `alpha` of
+    /// the way through the last step
+    pub fn draw_textured_at(&self, texture: &Sprite, alpha: f32) -> Vec<(u16, u16, char, Color)> {
+        self.draw_textured_from(texture, self.get_x_at(alpha))
+    }
+
+    fn draw_textured_from(&self, texture: &Sprite, x: f32) -> Vec<(u16, u16, char, Color)> {
+        let left = x.floor() as i32;
+        let top = self.get_top_y();
+        self.draw_from(x)
+            .into_iter()
+            .map(|(x, y, ch, color)| {
+                let tx = (x as i32 - left) as u16 % texture.get_width();
+                let ty = (y - top) % texture.get_height();
+                let (ch, color) = texture.get(tx, ty).unwrap_or((ch, color));
+                (x, y, ch, color)
+            })
+            .collect()
+    }
+
+    /// Returns the current world x position of the rectangle
+    pub fn get_x(&self) -> f32 {
         self.x
     }
     
-    /// Sets a new x position for the rectangle
-    pub fn set_x(&mut self, x: u16) {
+    /// Sets a new world x position for the rectangle
+    pub fn set_x(&mut self, x: f32) {
         self.x = x;
     }
 
-    /// Returns the leftmost x coordinate of the rectangle
+    /// Returns the x position a fraction `alpha` of the way from where the
+    /// last step started to where it is now
+    pub fn get_x_at(&self, alpha: f32) -> f32 {
+        // Counted back from now, so an alpha of 1.0 is exactly the current x
+        self.x - (self.x - self.previous_x) * (1.0 - alpha)
+    }
+
+    /// Makes the current position the start of the next step
+    pub fn start_step(&mut self) {
+        self.previous_x = self.x;
+    }
+
+    /// Returns the leftmost visible column of the rectangle
     pub fn get_leftmost_x(&self) -> u16 {
-        self.x
+        self.x as u16
     }
 
-    /// Returns the rightmost x coordinate of the rectangle
+    /// Returns the rightmost column of the rectangle
     pub fn get_rightmost_x(&self) -> u16 {
-        self.x + self.width - 1
+        (self.x.floor() + self.width as f32 - 1.0) as u16
     }
 
     /// Returns the top y coordinate of the rectangle
     pub fn get_top_y(&self) -> u16 {
-        self.screen_size.1-self.height
+        match self.anchor {
+            Anchor::Bottom => self.screen_size.1-self.height,
+            Anchor::Top => 0,
+        }
+    }
+
+    /// Returns the bottom y coordinate of the rectangle
+    pub fn get_bottom_y(&self) -> u16 {
+        match self.anchor {
+            Anchor::Bottom => self.screen_size.1 - 1,
+            Anchor::Top => self.height - 1,
+        }
+    }
+
+    /// Returns the rectangle's bounding box
+    ///
+    /// Like the bird's, the box is snapped to the cells the rectangle covers.
+    pub fn get_bounds(&self) -> Aabb {
+        let top = self.get_top_y();
+        Aabb::new(
+            self.x.floor(),
+            top as f32,
+            self.width as f32,
+            (self.get_bottom_y() - top + 1) as f32,
+        )
+    }
+
+    /// Returns the screen edge the rectangle grows from
+    pub fn get_anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    /// Moves the rectangle onto a screen of a new size
+    ///
+    /// The rectangle stays on its edge of the screen and its height is scaled
+    /// with the screen's. The horizontal position is kept. Resize the halves
+    /// of a pipe pair with [`Rect::resize_pair`] instead.
+    pub fn resize(&mut self, screen_size: (u16, u16)) {
+        let scale = screen_size.1 as f32 / self.screen_size.1 as f32;
+        let height = (self.height as f32 * scale).round() as u16;
+        self.height = height.clamp(1, screen_size.1.saturating_sub(1).max(1));
+        self.screen_size = screen_size;
+    }
+
+    /// Moves both halves of a pipe pair onto a screen of a new size
+    ///
+    /// Like [`Rect::resize`], but the pair is scaled as one so rounding cannot
+    /// close it: the gap is scaled too and kept at least [`MIN_PIPE_GAP`]
+    /// rows, taking the rows it needs from the taller half. Each half keeps
+    /// at least one row.
+    pub fn resize_pair(top: &mut Rect, bottom: &mut Rect, screen_size: (u16, u16)) {
+        let scale = screen_size.1 as f32 / top.screen_size.1 as f32;
+        let gap = top.screen_size.1.saturating_sub(top.height + bottom.height);
+        let gap = ((gap as f32 * scale).round() as u16)
+            .max(MIN_PIPE_GAP)
+            .min(screen_size.1.saturating_sub(2));
+        top.resize(screen_size);
+        bottom.resize(screen_size);
+        while top.height + bottom.height + gap > screen_size.1 {
+            let taller = if top.height >= bottom.height { &mut *top } else { &mut *bottom };
+            taller.height -= 1;
+        }
+    }
+
+    /// Returns the number of the obstacle this rectangle is part of
+    ///
+    /// Both halves of a pipe pair share a number, so they score as one.
+    pub fn get_obstacle(&self) -> u32 {
+        self.obstacle
+    }
+
+    /// Sets the number of the obstacle this rectangle is part of
+    pub fn set_obstacle(&mut self, obstacle: u32) {
+        self.obstacle = obstacle;
+    }
+
+    /// Returns the fewest free rows seen between the bird and the rectangle
+    /// while they shared columns, or `None` if they never did
+    pub fn get_clearance(&self) -> Option<u16> {
+        self.clearance
+    }
+
+    /// Records a clearance, keeping the smallest seen so far
+    pub fn record_clearance(&mut self, rows: u16) {
+        self.clearance = Some(self.clearance.map_or(rows, |closest| closest.min(rows)));
+    }
+
+    /// Returns `true` once the bird has passed the rectangle
+    pub fn is_passed(&self) -> bool {
+        self.passed
+    }
+
+    /// Marks the rectangle as passed so it is scored only once
+    pub fn mark_passed(&mut self) {
+        self.passed = true;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::difficulty::DifficultyCurve;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_rect_drawn_between_steps() {
+        let mut rect = Rect::new(10.0, 2, 1, Anchor::Bottom, Color::Blue, (20, 10));
+        rect.start_step();
+        rect.set_x(8.0);
+
+        assert_eq!(rect.get_x_at(0.0), 10.0);
+        assert_eq!(rect.get_x_at(0.5), 9.0);
+        assert_eq!(rect.draw_at(0.5)[0].0, 9);
+        assert_eq!(rect.draw_at(1.0), rect.draw());
+    }
 
     #[test]
     fn test_rect_creation() {
         let screen_size = (80, 24);
-        let rect = Rect::new(10, 5, 8, Color::Blue, screen_size);
+        let rect = Rect::new(10.0, 5, 8, Anchor::Bottom, Color::Blue, screen_size);
         
-        assert_eq!(rect.get_x(), 10);
+        assert_eq!(rect.get_x(), 10.0);
         assert_eq!(rect.get_leftmost_x(), 10);
         assert_eq!(rect.get_rightmost_x(), 14);
         assert_eq!(rect.get_top_y(), 16);
@@ -129,19 +377,40 @@ mod tests {
     #[test]
     fn test_rect_movement() {
         let screen_size = (80, 24);
-        let mut rect = Rect::new(10, 5, 8, Color::Blue, screen_size);
+        let mut rect = Rect::new(10.0, 5, 8, Anchor::Bottom, Color::Blue, screen_size);
         
-        rect.set_x(15);
-        assert_eq!(rect.get_x(), 15);
+        rect.set_x(15.0);
+        assert_eq!(rect.get_x(), 15.0);
         assert_eq!(rect.get_leftmost_x(), 15);
         assert_eq!(rect.get_rightmost_x(), 19);
+
+        // Fractional positions round down to the cell they cover
+        rect.set_x(14.6);
+        assert_eq!(rect.get_leftmost_x(), 14);
+        assert_eq!(rect.get_rightmost_x(), 18);
+    }
+
+    #[test]
+    fn test_rect_clipped_at_left_edge() {
+        let screen_size = (10, 10);
+        let rect = Rect::new(-1.5, 4, 2, Anchor::Bottom, Color::Red, screen_size);
+
+        assert_eq!(rect.get_leftmost_x(), 0);
+        assert_eq!(rect.get_rightmost_x(), 1);
+        // Columns -2 and -1 are off screen
+        assert_eq!(rect.draw().len(), 2 * 2);
+    }
+
+    fn normal() -> Difficulty {
+        DifficultyCurve::normal().start
     }
 
     #[test]
     fn test_random_rect() {
         let screen_size = (80, 24);
-        let max_width = 10;
-        let rect = Rect::random(max_width, screen_size);
+        let difficulty = normal();
+        let mut rng = StdRng::seed_from_u64(7);
+        let rect = Rect::random(&difficulty, screen_size, &mut rng);
         
         // Check that the rectangle fits within screen bounds
         assert!(rect.get_rightmost_x() <= screen_size.0);
@@ -149,14 +418,143 @@ mod tests {
         
         // Check that width is within specified range
         let width = rect.get_rightmost_x() - rect.get_leftmost_x() + 1;
-        assert!(width <= max_width);
-        assert!(width >= 2);
+        assert!(width <= difficulty.max_width);
+        assert!(width >= difficulty.min_width);
+    }
+
+    #[test]
+    fn test_wide_obstacles_fit_the_screen() {
+        let screen_size = (40, 16);
+        let difficulty = Difficulty { min_width: 90, max_width: 100, ..normal() };
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let rect = Rect::random_ceiling(&difficulty, screen_size, &mut rng);
+        assert_eq!((rect.get_x(), rect.get_rightmost_x()), (0.0, 39));
+        for rect in Rect::random_pair(&difficulty, screen_size, &mut rng) {
+            assert_eq!((rect.get_x(), rect.get_rightmost_x()), (0.0, 39));
+        }
+    }
+
+    #[test]
+    fn test_random_rect_is_reproducible() {
+        let screen_size = (80, 24);
+        let mut first = StdRng::seed_from_u64(1234);
+        let mut second = StdRng::seed_from_u64(1234);
+
+        for _ in 0..20 {
+            assert_eq!(
+                Rect::random(&normal(), screen_size, &mut first),
+                Rect::random(&normal(), screen_size, &mut second)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ceiling_rect() {
+        let screen_size = (80, 24);
+        let rect = Rect::new(10.0, 5, 8, Anchor::Top, Color::Blue, screen_size);
+
+        assert_eq!(rect.get_top_y(), 0);
+        assert_eq!(rect.get_bottom_y(), 7);
+        assert!(rect.draw().iter().all(|&(_, y, _, _)| y < 8));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let rect = Rect::random_ceiling(&normal(), screen_size, &mut rng);
+        assert_eq!(rect.get_anchor(), Anchor::Top);
+        assert_eq!(rect.get_top_y(), 0);
+    }
+
+    #[test]
+    fn test_pipe_pair_keeps_gap() {
+        let screen_size = (80, 24);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for gap in [4, 6, 9] {
+            for _ in 0..50 {
+                let difficulty = Difficulty { min_gap: gap, max_gap: gap, ..normal() };
+                let [top, bottom] = Rect::random_pair(&difficulty, screen_size, &mut rng);
+                assert_eq!(top.get_anchor(), Anchor::Top);
+                assert_eq!(bottom.get_anchor(), Anchor::Bottom);
+                assert_eq!(top.get_x(), bottom.get_x());
+                assert_eq!(bottom.get_top_y() - top.get_bottom_y() - 1, gap);
+                // The gap never opens onto the border
+                assert!(top.get_bottom_y() >= 1);
+                assert!(bottom.get_top_y() <= screen_size.1 - 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resize_scales_pipe_pair() {
+        let difficulty = Difficulty { min_gap: 6, max_gap: 6, ..normal() };
+        let mut pair = Rect::random_pair(&difficulty, (80, 24), &mut StdRng::seed_from_u64(2));
+        let x = pair[0].get_x();
+        let tops = [&pair[0], &pair[1]].map(|rect| rect.get_top_y());
+
+        for rect in &mut pair {
+            rect.resize((60, 48));
+        }
+        let [top, bottom] = &pair;
+        assert_eq!(top.get_top_y(), 0);
+        assert_eq!(bottom.get_bottom_y(), 47);
+        assert_eq!(bottom.get_top_y(), tops[1] * 2);
+        assert_eq!(bottom.get_top_y() - top.get_bottom_y() - 1, 12);
+        assert_eq!((top.get_x(), bottom.get_x()), (x, x));
+    }
+
+    #[test]
+    fn test_resize_keeps_pipe_gap_open() {
+        let mut top = Rect::new(50.0, 5, 18, Anchor::Top, Color::Blue, (80, 40));
+        let mut bottom = Rect::new(50.0, 5, 18, Anchor::Bottom, Color::Blue, (80, 40));
+
+        // Scaled on their own, both halves round up to 8 rows of 17
+        Rect::resize_pair(&mut top, &mut bottom, (80, 17));
+        assert_eq!(bottom.get_top_y() - top.get_bottom_y() - 1, MIN_PIPE_GAP);
+        assert_eq!((top.get_bottom_y(), bottom.get_top_y()), (5, 10));
+
+        // A gap that scales up is not squeezed back down
+        Rect::resize_pair(&mut top, &mut bottom, (80, 34));
+        assert_eq!(bottom.get_top_y() - top.get_bottom_y() - 1, 8);
+    }
+
+    #[test]
+    fn test_rect_texture_tiles_and_scrolls() {
+        let screen_size = (10, 10);
+        let texture = Sprite::from_rows(&["#=", "= "], Color::Red);
+        let mut rect = Rect::new(2.0, 3, 2, Anchor::Bottom, Color::Blue, screen_size);
+
+        let row = |rect: &Rect, y: u16| -> String {
+            let mut cells: Vec<_> = rect.draw_textured(&texture).into_iter().filter(|c| c.1 == y).collect();
+            cells.sort_by_key(|c| c.0);
+            cells.iter().map(|c| c.2).collect()
+        };
+        assert_eq!(row(&rect, 8), "#=#");
+        // The empty texture cell falls back to the plain look
+        assert_eq!(row(&rect, 9), "=*=");
+
+        // The pattern moves with the obstacle
+        rect.set_x(1.0);
+        assert_eq!(row(&rect, 8), "#=#");
+    }
+
+    #[test]
+    fn test_rect_keeps_closest_clearance() {
+        let mut rect = Rect::new(10.0, 5, 8, Anchor::Bottom, Color::Blue, (80, 24));
+        assert_eq!(rect.get_clearance(), None);
+
+        for rows in [4, 1, 3] {
+            rect.record_clearance(rows);
+        }
+        assert_eq!(rect.get_clearance(), Some(1));
+        assert!(!rect.is_passed());
+        rect.mark_passed();
+        assert!(rect.is_passed());
     }
 
     #[test]
     fn test_rect_drawing() {
         let screen_size = (10, 10);
-        let rect = Rect::new(2, 3, 4, Color::Blue, screen_size);
+        let rect = Rect::new(2.0, 3, 4, Anchor::Bottom, Color::Blue, screen_size);
         let points = rect.draw();
         
         // Check number of points (width * height)
@@ -0,0 +1,175 @@
+use std::fmt;
+use std::io::Result;
+use std::str::FromStr;
+
+/// Colors understood by every renderer
+///
+/// The named colors follow the classic 16-color terminal palette; `Rgb`
+/// is available to backends that support true color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    DarkGrey,
+    Red,
+    DarkRed,
+    Green,
+    DarkGreen,
+    Yellow,
+    DarkYellow,
+    Blue,
+    DarkBlue,
+    Magenta,
+    DarkMagenta,
+    Cyan,
+    DarkCyan,
+    White,
+    Grey,
+    Rgb { r: u8, g: u8, b: u8 },
+}
+
+/// Error returned when a color name is not recognized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown color '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parses a color name such as `"dark_red"` or a hex value such as `"#ff8800"`
+    ///
+    /// # Example
+    /// ```
+    /// use game_lib::render::Color;
+    ///
+    /// assert_eq!("magenta".parse(), Ok(Color::Magenta));
+    /// assert_eq!("#ff8800".parse(), Ok(Color::Rgb { r: 255, g: 136, b: 0 }));
+    /// ```
+    fn from_str(src: &str) -> std::result::Result<Self, Self::Err> {
+        let name = src.to_lowercase();
+        let color = match name.as_str() {
+            "black" => Color::Black,
+            "dark_grey" => Color::DarkGrey,
+            "red" => Color::Red,
+            "dark_red" => Color::DarkRed,
+            "green" => Color::Green,
+            "dark_green" => Color::DarkGreen,
+            "yellow" => Color::Yellow,
+            "dark_yellow" => Color::DarkYellow,
+            "blue" => Color::Blue,
+            "dark_blue" => Color::DarkBlue,
+            "magenta" => Color::Magenta,
+            "dark_magenta" => Color::DarkMagenta,
+            "cyan" => Color::Cyan,
+            "dark_cyan" => Color::DarkCyan,
+            "white" => Color::White,
+            "grey" => Color::Grey,
+            _ => return parse_hex(&name).ok_or_else(|| ParseColorError(src.to_string())),
+        };
+        Ok(color)
+    }
+}
+
+fn parse_hex(src: &str) -> Option<Color> {
+    let hex = src.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+    })
+}
+
+/// A single drawable cell: x, y, character and color
+pub type Cell = (u16, u16, char, Color);
+
+/// Output backend for the game
+///
+/// A renderer receives cells for the current frame and shows them on
+/// [`present`](Renderer::present). Game logic only talks to this trait, so
+/// the same game can drive a terminal, a window or an in-memory buffer.
+pub trait Renderer {
+    /// Returns the drawable area as (width, height)
+    fn size(&self) -> Result<(u16, u16)>;
+
+    /// Erases everything drawn so far
+    fn clear(&mut self) -> Result<()>;
+
+    /// Draws one character at the given position
+    fn draw_cell(&mut self, x: u16, y: u16, ch: char, color: Color) -> Result<()>;
+
+    /// Draws a set of cells, such as the output of [`Bird::draw`](crate::bird::Bird::draw)
+    fn draw_sprite(&mut self, cells: &[Cell]) -> Result<()> {
+        for &(x, y, ch, color) in cells {
+            self.draw_cell(x, y, ch, color)?;
+        }
+        Ok(())
+    }
+
+    /// Draws a line of text starting at the given position
+    fn draw_text(&mut self, x: u16, y: u16, text: &str, color: Color) -> Result<()> {
+        for (dx, ch) in text.chars().enumerate() {
+            self.draw_cell(x.saturating_add(dx as u16), y, ch, color)?;
+        }
+        Ok(())
+    }
+
+    /// Shows the frame drawn since the last call
+    fn present(&mut self) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color_names() {
+        assert_eq!("red".parse(), Ok(Color::Red));
+        assert_eq!("Dark_Grey".parse(), Ok(Color::DarkGrey));
+        assert_eq!("#000aff".parse(), Ok(Color::Rgb { r: 0, g: 10, b: 255 }));
+        assert!("plaid".parse::<Color>().is_err());
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("#gg0000".parse::<Color>().is_err());
+    }
+
+    struct Recorder(Vec<Cell>);
+
+    impl Renderer for Recorder {
+        fn size(&self) -> Result<(u16, u16)> {
+            Ok((10, 5))
+        }
+
+        fn clear(&mut self) -> Result<()> {
+            self.0.clear();
+            Ok(())
+        }
+
+        fn draw_cell(&mut self, x: u16, y: u16, ch: char, color: Color) -> Result<()> {
+            self.0.push((x, y, ch, color));
+            Ok(())
+        }
+
+        fn present(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_default_text_drawing() {
+        let mut recorder = Recorder(Vec::new());
+        recorder.draw_text(2, 1, "ab", Color::Green).unwrap();
+        assert_eq!(
+            recorder.0,
+            vec![(2, 1, 'a', Color::Green), (3, 1, 'b', Color::Green)]
+        );
+    }
+}
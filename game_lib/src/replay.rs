@@ -0,0 +1,548 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::animation::Animation;
+use crate::clock::ManualClock;
+use crate::config::{ConfigError, GameConfig};
+use crate::difficulty::Difficulty;
+use crate::fs_util;
+use crate::game::{GameEvent, GameSession};
+use crate::highscore;
+
+/// First line of a saved replay; the number is the format version
+const HEADER: &str = "# platform-game replay 1";
+
+/// Something the player did to a session, between two updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayInput {
+    /// The bird jumped
+    Jump,
+    /// The run was put on hold
+    Pause,
+    /// A paused run continued
+    Resume,
+    /// A paused or finished run was left for the title
+    ToTitle,
+    /// A new run started with this seed
+    Restart(u64),
+    /// The screen changed to this many columns and rows
+    Resize(u16, u16),
+}
+
+/// A recorded session that plays back exactly as it was played
+///
+/// Obstacles only depend on the seed and the game time, so the seed, the
+/// config and the inputs together with the update they came before are
+/// enough to play a session again. Record one by calling
+/// [`Replay::record`] for every input and [`Replay::tick`] after every
+/// [`Animation::update`], then play it back with a [`Player`].
+///
+/// Sprites are not part of a replay. A bird sprite changes the shape that
+/// collides, so play a replay back with the bird sprite it was recorded
+/// with.
+///
+/// # Example
+/// ```
+/// use game_lib::config::GameConfig;
+/// use game_lib::replay::{Player, Replay, ReplayInput};
+/// use std::time::Duration;
+///
+/// let mut replay = Replay::new(7, (80, 24), Duration::from_millis(40), Duration::ZERO, GameConfig::default());
+/// replay.tick();
+/// replay.record(ReplayInput::Jump);
+/// replay.tick();
+///
+/// let mut player = Player::new(Replay::parse(&replay.to_text()).unwrap()).unwrap();
+/// while player.step() {}
+/// assert_eq!(player.get_tick(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// Seed of the first run
+    pub seed: u64,
+    pub screen_size: (u16, u16),
+    /// Game time that passes per update
+    pub step: Duration,
+    /// Countdown of the recorded session
+    pub countdown: Duration,
+    pub config: GameConfig,
+    inputs: Vec<(u64, ReplayInput)>,
+    ticks: u64,
+}
+
+impl Replay {
+    /// Starts an empty recording of a session whose first run has `seed`
+    pub fn new(seed: u64, screen_size: (u16, u16), step: Duration, countdown: Duration, config: GameConfig) -> Self {
+        Self {
+            seed,
+            screen_size,
+            step,
+            countdown,
+            config,
+            inputs: Vec::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Records an input that comes before the next update
+    pub fn record(&mut self, input: ReplayInput) {
+        self.inputs.push((self.ticks, input));
+    }
+
+    /// Records that one update has passed
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// Returns the number of updates recorded
+    pub fn get_ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the inputs with the number of updates that came before each
+    pub fn get_inputs(&self) -> &[(u64, ReplayInput)] {
+        &self.inputs
+    }
+
+    /// Reads a replay saved with [`Replay::save`]
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::parse(&fs::read_to_string(path).map_err(ReplayError::Io)?)
+    }
+
+    /// Writes the replay, creating missing parent directories
+    ///
+    /// Like the high scores, the file is replaced in one step.
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        fs_util::write_atomic(path, &self.to_text()).map_err(ReplayError::Io)
+    }
+
+    /// Parses a saved replay
+    ///
+    /// # Errors
+    /// Besides malformed lines, a replay whose config fails
+    /// [`GameConfig::validate`] is rejected, since it could not be played.
+    pub fn parse(text: &str) -> Result<Self, ReplayError> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(ReplayError::Syntax { line: 1 });
+        }
+
+        let mut seed = None;
+        let mut screen_size = None;
+        let mut step = None;
+        let mut countdown = None;
+        let mut ticks = None;
+        let mut config = GameConfig::default();
+        let mut inputs = Vec::new();
+
+        for (index, line) in lines {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax = ReplayError::Syntax { line: index + 1 };
+            let mut fields = Fields(line.split('\t'));
+            let parsed = match fields.0.next() {
+                Some("seed") => fields.value().map(|value| seed = Some(value)),
+                Some("screen") => fields.pair().map(|pair| screen_size = Some(pair)),
+                Some("step_ns") => fields.value().map(|nanos| step = Some(Duration::from_nanos(nanos))),
+                Some("countdown_ns") => fields.value().map(|nanos| countdown = Some(Duration::from_nanos(nanos))),
+                Some("ticks") => fields.value().map(|value| ticks = Some(value)),
+                Some("gravity") => fields.value().map(|value| config.gravity = value),
+                Some("jump_velocity") => fields.value().map(|value| config.jump_velocity = value),
+                Some("update_interval") => fields.value().map(|value| config.update_interval = value),
+                Some("max_velocity") => fields.value().map(|value| config.max_velocity = value),
+                Some("bird_x") => fields.value().map(|value| config.bird_x = value),
+                Some("difficulty_start") => fields.difficulty().map(|value| config.difficulty.start = value),
+                Some("difficulty_limit") => fields.difficulty().map(|value| config.difficulty.limit = value),
+                Some("difficulty_rate") => fields.value().map(|value| config.difficulty.rate = value),
+                Some("score") => fields.score(&mut config),
+                Some("input") => fields.input().map(|input| inputs.push(input)),
+                _ => None,
+            };
+            // Trailing fields are as wrong as missing ones
+            if parsed.is_none() || fields.0.next().is_some() {
+                return Err(syntax);
+            }
+        }
+
+        config.validate().map_err(ReplayError::Config)?;
+        Ok(Self {
+            seed: seed.ok_or(ReplayError::Missing("seed"))?,
+            screen_size: screen_size.ok_or(ReplayError::Missing("screen"))?,
+            step: step.ok_or(ReplayError::Missing("step_ns"))?,
+            countdown: countdown.ok_or(ReplayError::Missing("countdown_ns"))?,
+            config,
+            inputs,
+            ticks: ticks.ok_or(ReplayError::Missing("ticks"))?,
+        })
+    }
+
+    /// Returns the replay in its saved form
+    pub fn to_text(&self) -> String {
+        let config = &self.config;
+        let difficulty = |level: &Difficulty| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                level.scroll_speed,
+                level.rect_interval,
+                level.min_width,
+                level.max_width,
+                level.min_height,
+                level.max_height,
+                level.min_gap,
+                level.max_gap
+            )
+        };
+
+        let mut text = format!("{}\n", HEADER);
+        text.push_str(&format!("seed\t{}\n", self.seed));
+        text.push_str(&format!("screen\t{}\t{}\n", self.screen_size.0, self.screen_size.1));
+        text.push_str(&format!("step_ns\t{}\n", self.step.as_nanos()));
+        text.push_str(&format!("countdown_ns\t{}\n", self.countdown.as_nanos()));
+        text.push_str(&format!("gravity\t{}\n", config.gravity));
+        text.push_str(&format!("jump_velocity\t{}\n", config.jump_velocity));
+        text.push_str(&format!("update_interval\t{}\n", config.update_interval));
+        text.push_str(&format!("max_velocity\t{}\n", config.max_velocity));
+        text.push_str(&format!("bird_x\t{}\n", config.bird_x));
+        text.push_str(&format!("difficulty_start\t{}\n", difficulty(&config.difficulty.start)));
+        text.push_str(&format!("difficulty_limit\t{}\n", difficulty(&config.difficulty.limit)));
+        text.push_str(&format!("difficulty_rate\t{}\n", config.difficulty.rate));
+        text.push_str(&format!(
+            "score\t{}\t{}\t{}\t{}\n",
+            config.score.pass_points, config.score.close_call_points, config.score.close_call_distance, config.score.max_combo
+        ));
+        text.push_str(&format!("ticks\t{}\n", self.ticks));
+        for (tick, input) in &self.inputs {
+            let name = match input {
+                ReplayInput::Jump => "jump".to_string(),
+                ReplayInput::Pause => "pause".to_string(),
+                ReplayInput::Resume => "resume".to_string(),
+                ReplayInput::ToTitle => "title".to_string(),
+                ReplayInput::Restart(seed) => format!("restart\t{}", seed),
+                ReplayInput::Resize(width, height) => format!("resize\t{}\t{}", width, height),
+            };
+            text.push_str(&format!("input\t{}\t{}\n", tick, name));
+        }
+        text
+    }
+}
+
+/// The tab-separated values of one line, after its key
+struct Fields<'a>(std::str::Split<'a, char>);
+
+impl Fields<'_> {
+    fn value<T: FromStr>(&mut self) -> Option<T> {
+        self.0.next()?.parse().ok()
+    }
+
+    fn pair<T: FromStr>(&mut self) -> Option<(T, T)> {
+        Some((self.value()?, self.value()?))
+    }
+
+    fn difficulty(&mut self) -> Option<Difficulty> {
+        Some(Difficulty {
+            scroll_speed: self.value()?,
+            rect_interval: self.value()?,
+            min_width: self.value()?,
+            max_width: self.value()?,
+            min_height: self.value()?,
+            max_height: self.value()?,
+            min_gap: self.value()?,
+            max_gap: self.value()?,
+        })
+    }
+
+    fn score(&mut self, config: &mut GameConfig) -> Option<()> {
+        config.score.pass_points = self.value()?;
+        config.score.close_call_points = self.value()?;
+        config.score.close_call_distance = self.value()?;
+        config.score.max_combo = self.value()?;
+        Some(())
+    }
+
+    fn input(&mut self) -> Option<(u64, ReplayInput)> {
+        let tick = self.value()?;
+        let input = match self.0.next()? {
+            "jump" => ReplayInput::Jump,
+            "pause" => ReplayInput::Pause,
+            "resume" => ReplayInput::Resume,
+            "title" => ReplayInput::ToTitle,
+            "restart" => ReplayInput::Restart(self.value()?),
+            "resize" => {
+                let (width, height) = self.pair()?;
+                ReplayInput::Resize(width, height)
+            }
+            _ => return None,
+        };
+        Some((tick, input))
+    }
+}
+
+/// Returns the usual place to keep `app`'s last replay
+///
+/// This is `last.replay` next to the [high scores](highscore::default_path).
+pub fn default_path(app: &str) -> Option<PathBuf> {
+    highscore::default_path(app).map(|path| path.with_file_name("last.replay"))
+}
+
+/// Plays a [`Replay`] back, one update at a time
+///
+/// The player owns the animation and session and feeds the recorded inputs
+/// to them before the same updates they came before, on a clock that moves
+/// by the recorded step per update.
+pub struct Player {
+    replay: Replay,
+    animation: Animation<ManualClock>,
+    session: GameSession,
+    tick: u64,
+    next_input: usize,
+}
+
+impl Player {
+    /// Prepares the replay's first run
+    ///
+    /// # Errors
+    /// Returns [`ReplayError::Config`] if the replay's config cannot be played.
+    pub fn new(replay: Replay) -> Result<Self, ReplayError> {
+        let animation = Animation::try_with_clock(replay.screen_size, replay.config, ManualClock::new())
+            .map_err(ReplayError::Config)?;
+        let mut player = Self {
+            session: GameSession::with_countdown(replay.countdown),
+            replay,
+            animation,
+            tick: 0,
+            next_input: 0,
+        };
+        player.rewind();
+        Ok(player)
+    }
+
+    /// Goes back to the start of the replay
+    pub fn rewind(&mut self) {
+        self.session = GameSession::with_countdown(self.replay.countdown);
+        self.animation.resize(self.replay.screen_size);
+        self.animation.restart_with_seed(self.replay.seed, &mut self.session);
+        self.tick = 0;
+        self.next_input = 0;
+    }
+
+    /// Plays the next update with the inputs that came before it
+    ///
+    /// Returns `false` without doing anything once the replay is over.
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        while let Some(&(tick, input)) = self.replay.inputs.get(self.next_input) {
+            if tick > self.tick {
+                break;
+            }
+            self.apply(input);
+            self.next_input += 1;
+        }
+        self.animation.get_clock().advance(self.replay.step);
+        self.animation.update(&mut self.session);
+        self.tick += 1;
+        true
+    }
+
+    fn apply(&mut self, input: ReplayInput) {
+        let event = match input {
+            ReplayInput::Jump => return self.animation.jump(),
+            ReplayInput::Restart(seed) => return self.animation.restart_with_seed(seed, &mut self.session),
+            ReplayInput::Resize(width, height) => return self.animation.resize((width, height)),
+            ReplayInput::Pause => GameEvent::Pause,
+            ReplayInput::Resume => GameEvent::Resume,
+            ReplayInput::ToTitle => GameEvent::ToTitle,
+        };
+        // The recorded session accepted this event in the same state, so a
+        // rejected one can only come from an edited file; it is skipped
+        let _ = self.session.handle(event);
+    }
+
+    /// Returns `true` once every recorded update has been played
+    pub fn is_finished(&self) -> bool {
+        self.tick >= self.replay.ticks
+    }
+
+    /// Returns the number of updates played so far
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the replay being played
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn get_animation(&self) -> &Animation<ManualClock> {
+        &self.animation
+    }
+
+    /// Returns the animation, to change how it is drawn
+    ///
+    /// Changing the bird sprite changes collisions, so the replay may no
+    /// longer play back as recorded.
+    pub fn animation_mut(&mut self) -> &mut Animation<ManualClock> {
+        &mut self.animation
+    }
+
+    pub fn get_session(&self) -> &GameSession {
+        &self.session
+    }
+}
+
+/// Reasons a replay could not be read or written
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// A line that is not part of a replay
+    Syntax { line: usize },
+    /// A required line that is not there
+    Missing(&'static str),
+    /// The recorded config cannot be played
+    Config(ConfigError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "cannot access replay: {}", err),
+            ReplayError::Syntax { line } => write!(f, "line {}: invalid replay line", line),
+            ReplayError::Missing(key) => write!(f, "replay has no '{}' line", key),
+            ReplayError::Config(err) => write!(f, "replay config: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use crate::game::GameStatus;
+    use crate::rect::Rect;
+
+    const STEP: Duration = Duration::from_millis(40);
+
+    /// What a replay has to reproduce after every update
+    fn snapshot(animation: &Animation<ManualClock>, session: &GameSession) -> (Vec<Rect>, (f32, f32), u32, GameStatus) {
+        (
+            animation.get_rectangles().to_vec(),
+            animation.get_bird().get_position(),
+            session.get_score(),
+            session.get_status(),
+        )
+    }
+
+    #[test]
+    fn test_player_reproduces_recording() {
+        let clock = ManualClock::new();
+        let config = GameConfig::default();
+        let countdown = Duration::from_millis(200);
+        let mut session = GameSession::with_countdown(countdown);
+        let mut animation = Animation::with_clock((80, 24), config, clock.clone());
+        let mut replay = Replay::new(11, (80, 24), STEP, countdown, config);
+        animation.restart_with_seed(11, &mut session);
+
+        let mut recorded = Vec::new();
+        for tick in 0..600u64 {
+            let input = match tick {
+                100 => Some(ReplayInput::Pause),
+                130 => Some(ReplayInput::Resume),
+                250 => Some(ReplayInput::Resize(100, 30)),
+                400 => Some(ReplayInput::Restart(99)),
+                _ if tick % 7 == 0 && session.get_status() == GameStatus::Running => Some(ReplayInput::Jump),
+                _ => None,
+            };
+            match input {
+                Some(ReplayInput::Jump) => animation.jump(),
+                Some(ReplayInput::Pause) => drop(session.handle(GameEvent::Pause)),
+                Some(ReplayInput::Resume) => drop(session.handle(GameEvent::Resume)),
+                Some(ReplayInput::Restart(seed)) => animation.restart_with_seed(seed, &mut session),
+                Some(ReplayInput::Resize(width, height)) => animation.resize((width, height)),
+                _ => {}
+            }
+            if let Some(input) = input {
+                replay.record(input);
+            }
+            clock.advance(STEP);
+            animation.update(&mut session);
+            replay.tick();
+            recorded.push(snapshot(&animation, &session));
+        }
+
+        let mut player = Player::new(Replay::parse(&replay.to_text()).unwrap()).unwrap();
+        for expected in &recorded {
+            assert!(player.step());
+            assert_eq!(&snapshot(player.get_animation(), player.get_session()), expected);
+        }
+        assert!(player.is_finished());
+        assert!(!player.step());
+        assert_eq!(player.get_animation().get_clock().now(), STEP * 600);
+
+        player.rewind();
+        assert!(player.step());
+        assert_eq!(&snapshot(player.get_animation(), player.get_session()), &recorded[0]);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let config = GameConfig {
+            gravity: 0.7,
+            jump_velocity: -2.25,
+            ..GameConfig::default()
+        };
+        let mut replay = Replay::new(u64::MAX, (100, 30), Duration::from_nanos(16_666_667), Duration::ZERO, config);
+        let inputs = [
+            ReplayInput::Jump,
+            ReplayInput::Pause,
+            ReplayInput::Resume,
+            ReplayInput::ToTitle,
+            ReplayInput::Restart(5),
+            ReplayInput::Resize(120, 40),
+        ];
+        for input in inputs {
+            replay.tick();
+            replay.record(input);
+        }
+
+        let parsed = Replay::parse(&replay.to_text()).unwrap();
+        assert_eq!(parsed, replay);
+        assert_eq!(parsed.get_inputs()[4], (5, ReplayInput::Restart(5)));
+        assert_eq!(parsed.get_inputs()[5], (6, ReplayInput::Resize(120, 40)));
+    }
+
+    #[test]
+    fn test_invalid_replays() {
+        let text = Replay::new(1, (80, 24), STEP, Duration::ZERO, GameConfig::default()).to_text();
+
+        assert!(matches!(Replay::parse("seed\t1\n"), Err(ReplayError::Syntax { line: 1 })));
+        assert!(matches!(
+            Replay::parse(&format!("{}input\t3\tfly\n", text)),
+            Err(ReplayError::Syntax { line: 16 })
+        ));
+        assert!(matches!(
+            Replay::parse(&text.replace("seed\t1\n", "seed\t1\t2\n")),
+            Err(ReplayError::Syntax { line: 2 })
+        ));
+        assert!(matches!(
+            Replay::parse(&text.replace("ticks\t0\n", "")),
+            Err(ReplayError::Missing("ticks"))
+        ));
+        assert!(matches!(
+            Replay::parse(&text.replace("gravity\t1\n", "gravity\t0\n")),
+            Err(ReplayError::Config(ConfigError::Gravity))
+        ));
+
+        // Replays built in code are checked when played
+        let config = GameConfig { max_velocity: 0.5, ..GameConfig::default() };
+        let replay = Replay::new(1, (80, 24), STEP, Duration::ZERO, config);
+        assert!(matches!(Player::new(replay), Err(ReplayError::Config(ConfigError::MaxVelocity))));
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::Result;
+
+use crate::animation::Animation;
+use crate::clock::Clock;
+use crate::collision::{Contact, Side};
+use crate::game::GameSession;
+use crate::highscore::HighScores;
+use crate::rect::{Anchor, Rect};
+use crate::render::{Color, Renderer};
+
+/// Colors of the parts of the screen that are not sprites
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub border: Color,
+    pub hud: Color,
+    pub game_over: Color,
+    pub message: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            border: Color::Magenta,
+            hud: Color::Green,
+            game_over: Color::Red,
+            message: Color::Green,
+        }
+    }
+}
+
+/// Draws a one-cell border around the edge of the screen
+pub fn draw_border<R: Renderer + ?Sized>(renderer: &mut R, color: Color) -> Result<()> {
+    let (width, height) = renderer.size()?;
+    for y in 0..height {
+        for x in 0..width {
+            if (y == 0 || y == height - 1) || (x == 0 || x == width - 1) {
+                renderer.draw_cell(x, y, '*', color)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Describes the collision that ended a run, e.g. "Landed on a floor block"
+pub fn describe_contact(contact: &Contact, rect: &Rect) -> String {
+    let obstacle = match rect.get_anchor() {
+        Anchor::Bottom => "floor block",
+        Anchor::Top => "ceiling block",
+    };
+    match contact.side {
+        Side::Left | Side::Right => format!("Crashed into a {}", obstacle),
+        Side::Top => format!("Landed on a {}", obstacle),
+        Side::Bottom => format!("Hit the underside of a {}", obstacle),
+    }
+}
+
+/// Draws a full frame of the game
+///
+/// Clears the renderer, then draws the border, the level and score HUD,
+/// the obstacles, the bird and, once the run is over, the game-over text
+/// with the cause of the crash.
+/// The frame is left unpresented so callers can draw overlays on top.
+pub fn draw_scene<R, C>(
+    renderer: &mut R,
+    animation: &Animation<C>,
+    game_session: &GameSession,
+    theme: &Theme,
+) -> Result<()>
+where
+    R: Renderer + ?Sized,
+    C: Clock,
+{
+    draw_scene_at(renderer, animation, game_session, theme, 1.0)
+}
+
+/// Draws a full frame of the game between two simulation steps
+///
+/// Like [`draw_scene`], but the bird and the obstacles are drawn a fraction
+/// `alpha` of the way through the last update, e.g. [`Tick::alpha`], so
+/// motion stays smooth when frames are drawn more often than the game
+/// updates. An `alpha` of 1.0 draws the current state.
+///
+/// [`Tick::alpha`]: crate::game_loop::Tick::alpha
+pub fn draw_scene_at<R, C>(
+    renderer: &mut R,
+    animation: &Animation<C>,
+    game_session: &GameSession,
+    theme: &Theme,
+    alpha: f32,
+) -> Result<()>
+where
+    R: Renderer + ?Sized,
+    C: Clock,
+{
+    let (width, height) = renderer.size()?;
+    renderer.clear()?;
+
+    draw_border(renderer, theme.border)?;
+
+    // Draw score and level
+    renderer.draw_text(2, 1, &format!("Level: {}", game_session.get_level()), theme.hud)?;
+    let mut score_text = format!("Score: {}", game_session.get_score());
+    if animation.get_combo() > 1 {
+        score_text.push_str(&format!("  Combo x{}", animation.get_combo()));
+    }
+    renderer.draw_text(2, 2, &score_text, theme.hud)?;
+
+    for rect in animation.get_rectangles() {
+        match animation.get_obstacle_texture() {
+            Some(texture) => renderer.draw_sprite(&rect.draw_textured_at(texture, alpha))?,
+            None => renderer.draw_sprite(&rect.draw_at(alpha))?,
+        }
+    }
+
+    renderer.draw_sprite(&animation.get_bird().draw_at(alpha))?;
+
+    if animation.is_game_over() {
+        let game_over_text = "GAME OVER! Press ESC to quit";
+        let restart_text = "Press R to restart";
+        let seed_text = format!("Seed: {}", game_session.get_seed());
+        let centered = |text: &str| width.saturating_sub(text.len() as u16) / 2;
+        let text_y = height / 2;
+
+        let cause = animation
+            .get_contact()
+            .and_then(|contact| Some(describe_contact(&contact, animation.get_rectangles().get(contact.entity)?)));
+        if let Some(cause) = cause {
+            renderer.draw_text(centered(&cause), text_y.saturating_sub(2), &cause, theme.game_over)?;
+        }
+        renderer.draw_text(centered(game_over_text), text_y, game_over_text, theme.game_over)?;
+        renderer.draw_text(centered(restart_text), text_y + 2, restart_text, theme.message)?;
+        renderer.draw_text(centered(&seed_text), text_y + 4, &seed_text, theme.message)?;
+    }
+
+    Ok(())
+}
+
+/// Draws the high-score table on a cleared screen inside the border
+///
+/// The entry at rank `highlight`, e.g. the one just added, stands out in
+/// the game-over color. Rows are cut off before the right border on narrow
+/// screens. The frame is left unpresented so callers can add hints below.
+pub fn draw_leaderboard<R>(
+    renderer: &mut R,
+    scores: &HighScores,
+    highlight: Option<usize>,
+    theme: &Theme,
+) -> Result<()>
+where
+    R: Renderer + ?Sized,
+{
+    let (width, _) = renderer.size()?;
+    renderer.clear()?;
+    draw_border(renderer, theme.border)?;
+
+    let title = "HIGH SCORES";
+    renderer.draw_text(width.saturating_sub(title.len() as u16) / 2, 2, title, theme.hud)?;
+
+    let x = 2;
+    let fit = |row: String| -> String { row.chars().take(width.saturating_sub(2 * x) as usize).collect() };
+    let header = format!("{:>2}  {:<12} {:>6} {:>3}  {:<10}  {}", "#", "Name", "Score", "Lvl", "Date", "Seed");
+    renderer.draw_text(x, 4, &fit(header), theme.hud)?;
+
+    if scores.get_entries().is_empty() {
+        renderer.draw_text(x, 5, &fit("No scores yet".to_string()), theme.message)?;
+    }
+    for (rank, entry) in scores.get_entries().iter().enumerate() {
+        let row = format!(
+            "{:>2}. {:<12} {:>6} {:>3}  {}  {}",
+            rank + 1,
+            entry.name,
+            entry.score,
+            entry.level,
+            entry.get_date_text(),
+            entry.seed
+        );
+        let color = if highlight == Some(rank) { theme.game_over } else { theme.message };
+        renderer.draw_text(x, 5 + rank as u16, &fit(row), color)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+/// Default points for every obstacle passed
+pub const PASS_POINTS: u32 = 1;
+
+/// Default extra points for a close call
+pub const CLOSE_CALL_POINTS: u32 = 2;
+
+/// Default largest clearance, in rows, that counts as a close call
+pub const CLOSE_CALL_DISTANCE: u16 = 1;
+
+/// Default cap on the close-call combo multiplier
+pub const MAX_COMBO: u32 = 4;
+
+/// How many points passing an obstacle is worth
+///
+/// Every obstacle the bird clears is worth `pass_points`. Slipping past it
+/// with no more than `close_call_distance` free rows between the bird and
+/// the obstacle is a close call and earns `close_call_points` on top. Close
+/// calls in a row build a combo that multiplies the bonus, up to
+/// `max_combo`; any ordinary pass breaks the streak.
+///
+/// # Example
+/// ```
+/// use game_lib::score::ScoreRules;
+///
+/// let rules = ScoreRules::default();
+///
+/// // A comfortable pass
+/// let pass = rules.score_pass(Some(5), 0);
+/// assert_eq!((pass.points, pass.combo), (1, 0));
+///
+/// // Two close calls in a row
+/// let first = rules.score_pass(Some(0), 0);
+/// let second = rules.score_pass(Some(1), first.combo);
+/// assert_eq!((first.points, second.points), (3, 5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreRules {
+    /// Points for every obstacle passed
+    pub pass_points: u32,
+    /// Extra points for a close call, before the combo multiplier
+    pub close_call_points: u32,
+    /// Largest clearance, in rows, that still counts as a close call
+    pub close_call_distance: u16,
+    /// Highest combo multiplier; 1 turns combos off
+    pub max_combo: u32,
+}
+
+impl Default for ScoreRules {
+    fn default() -> Self {
+        Self {
+            pass_points: PASS_POINTS,
+            close_call_points: CLOSE_CALL_POINTS,
+            close_call_distance: CLOSE_CALL_DISTANCE,
+            max_combo: MAX_COMBO,
+        }
+    }
+}
+
+/// Outcome of passing one obstacle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassScore {
+    /// Points earned, bonus included
+    pub points: u32,
+    /// Whether the pass was a close call
+    pub close_call: bool,
+    /// Close calls in a row, including this one
+    pub combo: u32,
+}
+
+impl ScoreRules {
+    /// Scores a pass given the bird's closest clearance to the obstacle
+    ///
+    /// `clearance` is the smallest number of free rows between the bird and
+    /// the obstacle while they shared columns, or `None` if they never did.
+    /// `combo` is the streak before this pass.
+    pub fn score_pass(&self, clearance: Option<u16>, combo: u32) -> PassScore {
+        let close_call = clearance.is_some_and(|rows| rows <= self.close_call_distance);
+        if !close_call {
+            return PassScore {
+                points: self.pass_points,
+                close_call,
+                combo: 0,
+            };
+        }
+
+        let combo = combo.saturating_add(1);
+        let bonus = self.close_call_points.saturating_mul(combo.min(self.max_combo));
+        PassScore {
+            points: self.pass_points.saturating_add(bonus),
+            close_call,
+            combo,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_is_capped_and_broken() {
+        let rules = ScoreRules { max_combo: 2, ..ScoreRules::default() };
+
+        let mut combo = 0;
+        let points: Vec<u32> = [Some(0), Some(1), Some(0), Some(3), Some(1)]
+            .into_iter()
+            .map(|clearance| {
+                let pass = rules.score_pass(clearance, combo);
+                combo = pass.combo;
+                pass.points
+            })
+            .collect();
+
+        // x1, x2, capped at x2, streak broken, starts over at x1
+        assert_eq!(points, [3, 5, 5, 1, 3]);
+    }
+
+    #[test]
+    fn test_unmeasured_pass_is_not_close() {
+        let pass = ScoreRules::default().score_pass(None, 3);
+        assert!(!pass.close_call);
+        assert_eq!(pass.points, PASS_POINTS);
+        assert_eq!(pass.combo, 0);
+    }
+}
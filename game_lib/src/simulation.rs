@@ -0,0 +1,335 @@
+use std::time::Duration;
+
+use crate::animation::Animation;
+use crate::clock::{Clock, ManualClock};
+use crate::collision::Aabb;
+use crate::config::{ConfigError, GameConfig};
+use crate::game::GameSession;
+use crate::rect::Anchor;
+
+/// Game time per tick used by [`Simulation::new`]
+///
+/// This is the example game's default frame interval, so a simulated tick
+/// matches a rendered frame.
+pub const DEFAULT_STEP: Duration = Duration::from_millis(40);
+
+/// What the player does in one tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Control {
+    /// Let the bird fall
+    #[default]
+    Idle,
+    /// Make the bird jump
+    Jump,
+}
+
+/// An obstacle the bird has not passed yet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Obstacle {
+    /// Number of the obstacle; both halves of a pipe pair share it
+    pub id: u32,
+    pub bounds: Aabb,
+    pub anchor: Anchor,
+}
+
+/// The state of a simulated run after a tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// Ticks played since the run started
+    pub tick: u64,
+    /// Top of the bird, in rows from the top of the screen
+    pub bird_y: f32,
+    /// Rows per update the bird is moving down (negative is up)
+    pub bird_velocity: f32,
+    /// The cells the bird collides with
+    pub bird: Aabb,
+    /// Obstacles still ahead of or next to the bird, nearest first
+    pub obstacles: Vec<Obstacle>,
+    pub score: u32,
+    pub level: u16,
+    /// The bird has crashed; further ticks change nothing
+    pub done: bool,
+}
+
+/// A run of the game without a terminal or a real clock
+///
+/// Every tick applies one [`Control`] and advances the game time by a fixed
+/// step, so runs with the same seed, config and controls always end the
+/// same way. The run starts right away, without a countdown.
+///
+/// A clone carries on from the same state with a clock of its own, so a bot
+/// can try out inputs ahead without touching the real run.
+///
+/// # Example
+/// ```
+/// use game_lib::config::GameConfig;
+/// use game_lib::simulation::{Control, Simulation};
+///
+/// let mut sim = Simulation::new((80, 24), GameConfig::default(), 42);
+///
+/// // Jump whenever the bird sinks below the middle of the screen
+/// let end = sim.run_with(500, |observation| {
+///     if observation.bird_y > 12.0 { Control::Jump } else { Control::Idle }
+/// });
+/// assert!(end.tick <= 500);
+/// ```
+pub struct Simulation {
+    animation: Animation<ManualClock>,
+    session: GameSession,
+    step: Duration,
+    tick: u64,
+}
+
+impl Clone for Simulation {
+    fn clone(&self) -> Self {
+        let clock = ManualClock::new();
+        clock.set(self.animation.get_clock().now());
+        let mut animation = self.animation.clone();
+        animation.set_clock(clock);
+        Self {
+            animation,
+            session: self.session.clone(),
+            step: self.step,
+            tick: self.tick,
+        }
+    }
+}
+
+impl Simulation {
+    /// Starts a run of `seed` that moves [`DEFAULT_STEP`] per tick
+    ///
+    /// # Panics
+    /// Panics if `config` fails [`GameConfig::validate`].
+    pub fn new(screen_size: (u16, u16), config: GameConfig, seed: u64) -> Self {
+        Self::with_step(screen_size, config, seed, DEFAULT_STEP)
+    }
+
+    /// Like [`Simulation::new`], but returns an error for a config that
+    /// fails [`GameConfig::validate`], e.g. one a bot was handed
+    pub fn try_new(screen_size: (u16, u16), config: GameConfig, seed: u64) -> Result<Self, ConfigError> {
+        Self::try_with_step(screen_size, config, seed, DEFAULT_STEP)
+    }
+
+    /// Starts a run of `seed` that moves `step` of game time per tick
+    ///
+    /// # Panics
+    /// Panics if `config` fails [`GameConfig::validate`].
+    pub fn with_step(screen_size: (u16, u16), config: GameConfig, seed: u64, step: Duration) -> Self {
+        Self::try_with_step(screen_size, config, seed, step).unwrap_or_else(|err| panic!("invalid game config: {err}"))
+    }
+
+    /// Like [`Simulation::with_step`], but returns an error for a config
+    /// that fails [`GameConfig::validate`]
+    pub fn try_with_step(
+        screen_size: (u16, u16),
+        config: GameConfig,
+        seed: u64,
+        step: Duration,
+    ) -> Result<Self, ConfigError> {
+        let mut simulation = Self {
+            animation: Animation::try_with_clock(screen_size, config, ManualClock::new())?,
+            session: GameSession::new(),
+            step,
+            tick: 0,
+        };
+        simulation.reset(seed);
+        Ok(simulation)
+    }
+
+    /// Starts over with a new run of `seed`
+    pub fn reset(&mut self, seed: u64) {
+        self.animation.restart_with_seed(seed, &mut self.session);
+        self.tick = 0;
+    }
+
+    /// Plays one tick with `control` and returns the state after it
+    ///
+    /// Once the run is done, the state no longer changes.
+    pub fn step(&mut self, control: Control) -> Observation {
+        if !self.is_done() {
+            if control == Control::Jump {
+                self.animation.jump();
+            }
+            self.animation.get_clock().advance(self.step);
+            self.animation.update(&mut self.session);
+            self.tick += 1;
+        }
+        self.observe()
+    }
+
+    /// Plays one tick per control, stopping early if the bird crashes
+    pub fn run<I: IntoIterator<Item = Control>>(&mut self, controls: I) -> Observation {
+        for control in controls {
+            if self.is_done() {
+                break;
+            }
+            self.step(control);
+        }
+        self.observe()
+    }
+
+    /// Plays up to `ticks` ticks, asking `policy` for each tick's control
+    ///
+    /// The policy sees the state before the tick. Stops early if the bird
+    /// crashes.
+    pub fn run_with<F>(&mut self, ticks: u64, mut policy: F) -> Observation
+    where
+        F: FnMut(&Observation) -> Control,
+    {
+        let mut observation = self.observe();
+        for _ in 0..ticks {
+            if observation.done {
+                break;
+            }
+            observation = self.step(policy(&observation));
+        }
+        observation
+    }
+
+    /// Returns the current state of the run
+    pub fn observe(&self) -> Observation {
+        let bird = self.animation.get_bird();
+        let bounds = bird.get_bounds();
+        let mut obstacles: Vec<Obstacle> = self
+            .animation
+            .get_rectangles()
+            .iter()
+            .filter(|rect| rect.get_bounds().right() > bounds.left())
+            .map(|rect| Obstacle {
+                id: rect.get_obstacle(),
+                bounds: rect.get_bounds(),
+                anchor: rect.get_anchor(),
+            })
+            .collect();
+        obstacles.sort_by(|a, b| a.bounds.left().total_cmp(&b.bounds.left()));
+
+        Observation {
+            tick: self.tick,
+            bird_y: bird.get_position().1,
+            bird_velocity: bird.get_velocity(),
+            bird: bounds,
+            obstacles,
+            score: self.session.get_score(),
+            level: self.session.get_level(),
+            done: self.is_done(),
+        }
+    }
+
+    /// Returns `true` once the bird has crashed
+    pub fn is_done(&self) -> bool {
+        self.animation.is_game_over()
+    }
+
+    /// Returns the number of ticks played since the run started
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the seed of the current run
+    pub fn get_seed(&self) -> u64 {
+        self.animation.get_seed()
+    }
+
+    /// Returns the animation, to draw the run or inspect it further
+    pub fn get_animation(&self) -> &Animation<ManualClock> {
+        &self.animation
+    }
+
+    /// Returns the animation, to change how it is drawn
+    pub fn animation_mut(&mut self) -> &mut Animation<ManualClock> {
+        &mut self.animation
+    }
+
+    pub fn get_session(&self) -> &GameSession {
+        &self.session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Jumps every `period` ticks
+    fn every(period: u64) -> impl FnMut(&Observation) -> Control {
+        move |observation| {
+            if observation.tick % period == 0 {
+                Control::Jump
+            } else {
+                Control::Idle
+            }
+        }
+    }
+
+    #[test]
+    fn test_runs_are_deterministic() {
+        let mut first = Simulation::new((80, 24), GameConfig::default(), 5);
+        let mut second = Simulation::new((80, 24), GameConfig::default(), 5);
+        for tick in 0..300 {
+            let control = if tick % 6 == 0 { Control::Jump } else { Control::Idle };
+            assert_eq!(first.step(control), second.step(control));
+        }
+
+        let end = first.observe();
+        first.reset(5);
+        assert_eq!(first.observe().tick, 0);
+        assert_eq!(first.run_with(300, every(6)), end);
+    }
+
+    #[test]
+    fn test_clones_run_on_their_own() {
+        let mut sim = Simulation::new((80, 24), GameConfig::default(), 5);
+        let start = sim.run_with(50, every(6));
+        assert!(!start.done);
+        let mut copy = sim.clone();
+        assert_eq!(copy.observe(), start);
+
+        // The copy goes its own way without moving the original
+        let fallen = copy.run(std::iter::repeat(Control::Idle).take(5));
+        assert_eq!(sim.get_animation().get_clock().now(), DEFAULT_STEP * 50);
+        assert_eq!(sim.observe(), start);
+        assert_ne!(fallen, start);
+
+        // ...and the same inputs lead both to the same place
+        assert_eq!(sim.run(std::iter::repeat(Control::Idle).take(5)), fallen);
+    }
+
+    #[test]
+    fn test_run_stops_at_crash() {
+        for seed in 0..5 {
+            let mut sim = Simulation::new((80, 24), GameConfig::default(), seed);
+            let end = sim.run(std::iter::repeat(Control::Idle).take(5000));
+            assert!(end.done, "seed {} never crashed", seed);
+            assert!(end.tick < 5000);
+
+            // A finished run stays put
+            assert_eq!(sim.step(Control::Jump), end);
+        }
+    }
+
+    #[test]
+    fn test_bad_config_is_an_error() {
+        let config = GameConfig { jump_velocity: 1.0, ..GameConfig::default() };
+        assert!(matches!(
+            Simulation::try_new((80, 24), config, 1),
+            Err(ConfigError::JumpVelocity)
+        ));
+        assert!(Simulation::try_new((80, 24), GameConfig::default(), 1).is_ok());
+    }
+
+    #[test]
+    fn test_observation_lists_upcoming_obstacles() {
+        let mut sim = Simulation::new((80, 24), GameConfig::default(), 9);
+        let observation = sim.run_with(200, every(5));
+
+        assert_eq!(observation.bird, sim.get_animation().get_bird().get_bounds());
+        assert_eq!(observation.bird_y, sim.get_animation().get_bird().get_position().1);
+        assert!(!observation.obstacles.is_empty());
+        for pair in observation.obstacles.windows(2) {
+            assert!(pair[0].bounds.left() <= pair[1].bounds.left());
+        }
+        assert!(observation
+            .obstacles
+            .iter()
+            .all(|obstacle| obstacle.bounds.right() > observation.bird.left()));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::bird::Bird;
+use crate::collision::Aabb;
+use crate::config::GameConfig;
+use crate::difficulty::DifficultyCurve;
+use crate::game::GameSession;
+use crate::rect::Rect;
+use crate::sprite::Sprite;
+
+/// Every height and vertical speed the bird could be at if played perfectly
+///
+/// The set starts at the bird's state. Each update adds the outcome of both
+/// letting the bird fall and making it jump from every state, with the
+/// bird's own physics, and drops the states that crash. While the set is
+/// not empty, some sequence of inputs is still alive.
+///
+/// States on the same row with the same velocity are merged into the one
+/// nearest the top of the row. Velocities only ever count updates since the
+/// last jump or bounce, so this keeps the set within rows times velocities
+/// whatever the physics. Every state kept is still one the bird can reach.
+///
+/// # Example
+/// ```
+/// use game_lib::bird::Bird;
+/// use game_lib::config::GameConfig;
+/// use game_lib::solvability::Reachable;
+///
+/// let config = GameConfig::default();
+/// let bird = Bird::new((80, 24), &config);
+/// let mut reachable = Reachable::new(&bird, &config);
+///
+/// reachable.advance(&bird, bird.get_sprite(), &[]);
+/// // Falling and jumping lead to different states
+/// assert_eq!(reachable.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reachable {
+    /// Height and velocity pairs, sorted by row and velocity, one per pair
+    states: Vec<(f32, f32)>,
+    jump_velocity: f32,
+}
+
+impl Reachable {
+    /// Starts from the bird's current state
+    pub fn new(bird: &Bird, config: &GameConfig) -> Self {
+        Self {
+            states: vec![(bird.get_position().1, bird.get_velocity())],
+            jump_velocity: config.jump_velocity,
+        }
+    }
+
+    /// Plays one update from every state
+    ///
+    /// `sprite` is the bird's frame after the update and `obstacles` are
+    /// where the obstacles are after it.
+    pub fn advance(&mut self, bird: &Bird, sprite: &Sprite, obstacles: &[Aabb]) {
+        let x = bird.get_position().0.floor();
+        let mut next = Vec::with_capacity(self.states.len() * 2);
+        for &(y, velocity) in &self.states {
+            for velocity in [velocity, self.jump_velocity] {
+                let (y, velocity) = bird.fall(y, velocity);
+                let hit = obstacles
+                    .iter()
+                    .any(|obstacle| sprite.penetration(x, y.floor(), obstacle).is_some());
+                if !hit {
+                    next.push((y, velocity));
+                }
+            }
+        }
+        next.sort_by(|a, b| {
+            a.0.floor()
+                .total_cmp(&b.0.floor())
+                .then(a.1.total_cmp(&b.1))
+                .then(a.0.total_cmp(&b.0))
+        });
+        next.dedup_by(|a, b| a.0.floor() == b.0.floor() && a.1 == b.1);
+        self.states = next;
+    }
+
+    /// Returns `true` if the bird can crash, whatever the player does
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Returns the number of distinct states
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns the states as height and velocity pairs
+    pub fn get_states(&self) -> &[(f32, f32)] {
+        &self.states
+    }
+}
+
+/// Where the obstacles will be over the next updates
+///
+/// Yields the obstacle boxes after each update, scrolled the way
+/// [`Animation::update`](crate::animation::Animation::update) does: at the
+/// speed of the current level, which goes up as the bird passes obstacles.
+/// Obstacles that have not spawned yet are not part of it.
+///
+/// # Example
+/// ```
+/// use game_lib::animation::Animation;
+/// use game_lib::config::GameConfig;
+/// use game_lib::game::GameSession;
+/// use game_lib::solvability::Forecast;
+///
+/// let animation = Animation::new((80, 24), GameConfig::default());
+/// let mut forecast = Forecast::new(
+///     animation.get_rectangles(),
+///     animation.get_bird(),
+///     GameConfig::default().difficulty,
+///     &GameSession::new(),
+/// );
+/// // Nothing has spawned yet
+/// assert!(forecast.is_clear());
+/// assert_eq!(forecast.next(), Some(Vec::new()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    /// (x, obstacle, bounds, passed) of every rectangle
+    rects: Vec<(f32, u32, Aabb, bool)>,
+    bird: Aabb,
+    curve: DifficultyCurve,
+    passed: u32,
+    level: u16,
+}
+
+impl Forecast {
+    /// Starts from `rects` as they are now, with the session's counts so far
+    pub fn new<'a, I>(rects: I, bird: &Bird, curve: DifficultyCurve, session: &GameSession) -> Self
+    where
+        I: IntoIterator<Item = &'a Rect>,
+    {
+        Self {
+            rects: rects
+                .into_iter()
+                .map(|rect| (rect.get_x(), rect.get_obstacle(), rect.get_bounds(), rect.is_passed()))
+                .collect(),
+            bird: bird.get_bounds(),
+            curve,
+            passed: session.get_passed(),
+            level: session.get_level(),
+        }
+    }
+
+    /// Returns `true` once the bird has got past every obstacle
+    pub fn is_clear(&self) -> bool {
+        self.rects.iter().all(|&(.., passed)| passed)
+    }
+}
+
+impl Iterator for Forecast {
+    type Item = Vec<Aabb>;
+
+    fn next(&mut self) -> Option<Vec<Aabb>> {
+        let speed = self.curve.at_level(self.level).scroll_speed;
+        self.rects.retain(|&(x, ..)| x > 0.0);
+        for (x, _, bounds, _) in &mut self.rects {
+            *x -= speed;
+            bounds.x = x.floor();
+        }
+        let obstacles = self.rects.iter().map(|&(_, _, bounds, _)| bounds).collect();
+
+        // Passing is scored after the update, so it speeds up the next one
+        let bird = self.bird;
+        while let Some(obstacle) = self
+            .rects
+            .iter()
+            .find(|&&(_, _, bounds, passed)| !passed && bounds.right() <= bird.left())
+            .map(|&(_, obstacle, ..)| obstacle)
+        {
+            for rect in self.rects.iter_mut().filter(|rect| rect.1 == obstacle) {
+                rect.3 = true;
+            }
+            self.passed += 1;
+            if self.passed % 5 == 0 {
+                self.level = self.level.saturating_add(1);
+            }
+        }
+        Some(obstacles)
+    }
+}
+
+/// Checks that a perfect player gets past every obstacle of `forecast`
+///
+/// Returns `false` if some obstacle cannot be flown past from any of the
+/// `reachable` states, whatever the player does.
+///
+/// # Example
+/// ```
+/// use game_lib::animation::Animation;
+/// use game_lib::config::GameConfig;
+/// use game_lib::game::GameSession;
+/// use game_lib::solvability::{can_pass_all, Forecast, Reachable};
+///
+/// let config = GameConfig::default();
+/// let animation = Animation::new((80, 24), config);
+/// let bird = animation.get_bird();
+/// let forecast = Forecast::new(animation.get_rectangles(), bird, config.difficulty, &GameSession::new());
+///
+/// assert!(can_pass_all(&Reachable::new(bird, &config), bird, forecast));
+/// ```
+pub fn can_pass_all(reachable: &Reachable, bird: &Bird, mut forecast: Forecast) -> bool {
+    let mut reachable = reachable.clone();
+    let mut updates = 0;
+    while !forecast.is_clear() {
+        updates += 1;
+        let obstacles = forecast.next().unwrap_or_default();
+        reachable.advance(bird, bird.get_sprite_after(updates), &obstacles);
+        if reachable.is_empty() {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autopilot::Autopilot;
+    use crate::difficulty::{Difficulty, Preset};
+    use crate::simulation::{Control, Simulation};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_narrow_gap_is_impossible() {
+        let config = GameConfig::default();
+        let screen_size = (80, 24);
+        let bird = Bird::new(screen_size, &config);
+        let reachable = Reachable::new(&bird, &config);
+        let curve = DifficultyCurve::normal();
+        let session = GameSession::new();
+        let pipe = |gap: u16, width: u16| {
+            let difficulty = Difficulty {
+                min_width: width,
+                max_width: width,
+                min_gap: gap,
+                max_gap: gap,
+                ..curve.start
+            };
+            let pair = Rect::random_pair(&difficulty, screen_size, &mut StdRng::seed_from_u64(0));
+            Forecast::new(&pair, &bird, curve, &session)
+        };
+
+        // Hovering takes more rows than a long pipe with a 5-row gap leaves
+        assert!(!can_pass_all(&reachable, &bird, pipe(5, 10)));
+        assert!(can_pass_all(&reachable, &bird, pipe(5, 2)));
+        assert!(can_pass_all(&reachable, &bird, pipe(7, 10)));
+    }
+
+    #[test]
+    fn test_states_stay_bounded() {
+        // Sums of these never land on the same floats twice
+        let config = GameConfig {
+            gravity: 0.45,
+            jump_velocity: -1.9,
+            ..GameConfig::default()
+        };
+        let screen_size = (80, 24);
+        let bird = Bird::new(screen_size, &config);
+        let mut reachable = Reachable::new(&bird, &config);
+        let velocities = ((config.max_velocity - config.jump_velocity) / config.gravity) as usize + 2;
+        for _ in 0..80 {
+            reachable.advance(&bird, bird.get_sprite(), &[]);
+            // Three velocity chains: from the start, a jump and a bounce
+            assert!(reachable.len() <= screen_size.1 as usize * velocities * 3);
+        }
+        assert!(!reachable.is_empty());
+    }
+
+    /// Tries every run of jumps and falls from `sim` until obstacle `id` is
+    /// behind the bird, skipping states already tried at the same tick
+    fn find_way_past(sim: &Simulation, id: u32, tried: &mut HashSet<(u64, u32, u32)>) -> bool {
+        let observation = sim.observe();
+        if observation.done {
+            return false;
+        }
+        if observation.obstacles.iter().all(|obstacle| obstacle.id != id) {
+            return true;
+        }
+        let state = (observation.tick, observation.bird_y.to_bits(), observation.bird_velocity.to_bits());
+        if !tried.insert(state) {
+            return false;
+        }
+        [Control::Idle, Control::Jump].into_iter().any(|control| {
+            let mut next = sim.clone();
+            next.step(control);
+            find_way_past(&next, id, tried)
+        })
+    }
+
+    /// Property: whatever the seed, preset and physics, every obstacle
+    /// leaves a way through from where the bird was when it spawned
+    ///
+    /// Checked by playing the real game forward from each spawn rather than
+    /// with the solver the generator uses.
+    #[test]
+    fn test_spawns_leave_a_way_through() {
+        let mut rng = StdRng::seed_from_u64(24);
+        let mut spawns = 0;
+        for seed in 0..24 {
+            let preset = [Preset::Easy, Preset::Normal, Preset::Hard][rng.gen_range(0..3)];
+            let config = GameConfig {
+                gravity: rng.gen_range(0.45..1.2),
+                jump_velocity: rng.gen_range(-3.5..-1.9),
+                difficulty: preset.curve(),
+                ..GameConfig::default()
+            };
+            let screen_size = (rng.gen_range(40..120), rng.gen_range(16..40));
+            let mut sim = Simulation::new(screen_size, config, seed);
+            let mut latest = 0;
+            for _ in 0..300 {
+                // Random inputs, so spawns see states a good player avoids
+                let control = if rng.gen_ratio(1, 6) { Control::Jump } else { Control::Idle };
+                let observation = sim.step(control);
+                let Some(obstacle) = observation.obstacles.iter().map(|obstacle| obstacle.id).max() else {
+                    continue;
+                };
+                if obstacle > latest {
+                    latest = obstacle;
+                    spawns += 1;
+                    assert!(
+                        find_way_past(&sim, obstacle, &mut HashSet::new()),
+                        "seed {} ({:?}) spawned a wall at tick {}",
+                        seed,
+                        config,
+                        sim.get_tick()
+                    );
+                }
+                if observation.done {
+                    // Crashing is the random player's fault; start over
+                    sim.reset(rng.gen());
+                    latest = 0;
+                }
+            }
+        }
+        assert!(spawns > 100, "only {} spawns", spawns);
+    }
+
+    #[test]
+    fn test_autopilot_survives_every_preset() {
+        let autopilot = Autopilot::new();
+        for preset in [Preset::Easy, Preset::Normal, Preset::Hard] {
+            let config = GameConfig {
+                difficulty: preset.curve(),
+                ..GameConfig::default()
+            };
+            let mut sim = Simulation::new((80, 24), config, 7);
+            for _ in 0..1500 {
+                sim.step(autopilot.decide(sim.get_animation(), sim.get_session()));
+            }
+            let end = sim.observe();
+            assert!(!end.done, "{:?} crashed at tick {}", preset, end.tick);
+            // Obstacles keep coming rather than being skipped
+            assert!(end.level > 5, "{:?} only reached level {}", preset, end.level);
+        }
+    }
+}
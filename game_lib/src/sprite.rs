@@ -0,0 +1,225 @@
+use crate::collision::{Aabb, Side};
+use crate::render::{Cell, Color};
+
+/// A small picture made of terminal cells
+///
+/// Every cell is either solid, with a character and color, or empty. Only
+/// solid cells are drawn and only solid cells collide, so what the player
+/// sees is exactly what can be hit.
+///
+/// # Example
+/// ```
+/// use game_lib::render::Color;
+/// use game_lib::sprite::Sprite;
+///
+/// let sprite = Sprite::from_rows(&[" o ", "/|\\"], Color::White);
+/// assert_eq!((sprite.get_width(), sprite.get_height()), (3, 2));
+/// assert!(!sprite.is_solid(0, 0));
+/// assert!(sprite.is_solid(1, 0));
+/// assert_eq!(sprite.draw(10, 5).len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    width: u16,
+    height: u16,
+    cells: Vec<Option<(char, Color)>>,
+}
+
+impl Sprite {
+    /// Creates a sprite from rows of text in a single color
+    ///
+    /// Spaces are empty cells. Shorter rows are padded with empty cells to
+    /// the width of the longest row.
+    pub fn from_rows(rows: &[&str], color: Color) -> Self {
+        Self::from_rows_with(rows, |_| color)
+    }
+
+    /// Creates a sprite from rows of text, coloring each character with `color_of`
+    ///
+    /// Follows the same rules as [`Sprite::from_rows`].
+    pub fn from_rows_with<F>(rows: &[&str], mut color_of: F) -> Self
+    where
+        F: FnMut(char) -> Color,
+    {
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows {
+            let mut chars = row.chars();
+            for _ in 0..width {
+                cells.push(chars.next().filter(|&ch| ch != ' ').map(|ch| (ch, color_of(ch))));
+            }
+        }
+        Self {
+            width: width as u16,
+            height: rows.len() as u16,
+            cells,
+        }
+    }
+
+    /// Returns the width in cells
+    pub fn get_width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height in cells
+    pub fn get_height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the character and color at a cell, or `None` if it is empty
+    ///
+    /// Cells outside the sprite are empty.
+    pub fn get(&self, x: u16, y: u16) -> Option<(char, Color)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells[(y * self.width + x) as usize]
+    }
+
+    /// Returns `true` if the cell is part of the sprite's shape
+    pub fn is_solid(&self, x: u16, y: u16) -> bool {
+        self.get(x, y).is_some()
+    }
+
+    /// Returns the solid cells with the sprite's top-left corner at `(x, y)`
+    pub fn draw(&self, x: u16, y: u16) -> Vec<Cell> {
+        self.solid_cells()
+            .map(|(dx, dy, ch, color)| (x + dx, y + dy, ch, color))
+            .collect()
+    }
+
+    /// Returns the box around the whole sprite placed at `(x, y)`
+    pub fn get_bounds(&self, x: f32, y: f32) -> Aabb {
+        Aabb::new(x, y, self.width as f32, self.height as f32)
+    }
+
+    /// Tests the sprite placed at `(x, y)` against an obstacle box
+    ///
+    /// Only solid cells count. The side and depth are those of the smallest
+    /// box around the solid cells that overlap the obstacle.
+    pub fn penetration(&self, x: f32, y: f32, obstacle: &Aabb) -> Option<(Side, f32)> {
+        if !self.get_bounds(x, y).overlaps(obstacle) {
+            return None;
+        }
+
+        self.solid_cells()
+            .map(|(dx, dy, _, _)| Aabb::new(x + dx as f32, y + dy as f32, 1.0, 1.0))
+            .filter(|cell| cell.overlaps(obstacle))
+            .reduce(|hit, cell| hit.union(&cell))?
+            .penetration(obstacle)
+    }
+
+    fn solid_cells(&self) -> impl Iterator<Item = (u16, u16, char, Color)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width).filter_map(move |x| self.get(x, y).map(|(ch, color)| (x, y, ch, color)))
+        })
+    }
+}
+
+/// A sequence of sprites shown one after another, e.g. a wing flap
+///
+/// Each frame stays up for a fixed number of ticks before the next one is
+/// shown; after the last frame the animation starts over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedSprite {
+    frames: Vec<Sprite>,
+    ticks_per_frame: u32,
+}
+
+impl AnimatedSprite {
+    /// Creates an animation from its frames
+    ///
+    /// # Panics
+    /// Panics if `frames` is empty or `ticks_per_frame` is zero.
+    pub fn new(frames: Vec<Sprite>, ticks_per_frame: u32) -> Self {
+        assert!(!frames.is_empty(), "an animated sprite needs at least one frame");
+        assert!(ticks_per_frame > 0, "ticks per frame should be positive");
+        Self {
+            frames,
+            ticks_per_frame,
+        }
+    }
+
+    /// Returns the frame shown at `tick`
+    pub fn frame_at(&self, tick: u32) -> &Sprite {
+        let index = (tick / self.ticks_per_frame) as usize % self.frames.len();
+        &self.frames[index]
+    }
+
+    /// Returns all frames in order
+    pub fn get_frames(&self) -> &[Sprite] {
+        &self.frames
+    }
+
+    /// Returns how many ticks each frame is shown for
+    pub fn get_ticks_per_frame(&self) -> u32 {
+        self.ticks_per_frame
+    }
+}
+
+impl From<Sprite> for AnimatedSprite {
+    /// Wraps a still sprite as a one-frame animation
+    fn from(sprite: Sprite) -> Self {
+        Self::new(vec![sprite], 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bird() -> Sprite {
+        Sprite::from_rows(&["   |>", "|::| "], Color::Yellow)
+    }
+
+    #[test]
+    fn test_mask_from_rows() {
+        let sprite = Sprite::from_rows(&["ab", "c"], Color::Red);
+
+        assert_eq!(sprite.get(0, 0), Some(('a', Color::Red)));
+        assert_eq!(sprite.get(1, 1), None); // padding
+        assert_eq!(sprite.get(5, 0), None); // outside
+        assert_eq!(sprite.draw(0, 0).len(), 3);
+    }
+
+    #[test]
+    fn test_animation_cycles_frames() {
+        let frames = vec![
+            Sprite::from_rows(&["a"], Color::Red),
+            Sprite::from_rows(&["b"], Color::Red),
+        ];
+        let animation = AnimatedSprite::new(frames, 2);
+
+        let shown: String = (0..8)
+            .map(|tick| animation.frame_at(tick).get(0, 0).unwrap().0)
+            .collect();
+        assert_eq!(shown, "aabbaabb");
+    }
+
+    #[test]
+    fn test_empty_cells_do_not_collide() {
+        let sprite = bird();
+
+        // Touches only the blank top-left corner of the bird
+        let corner = Aabb::new(8.0, 8.0, 3.0, 3.0);
+        assert!(sprite.get_bounds(10.0, 10.0).overlaps(&corner));
+        assert_eq!(sprite.penetration(10.0, 10.0, &corner), None);
+
+        // Reaches the solid '|' in the bottom-left corner
+        let lower = Aabb::new(8.0, 11.0, 3.0, 3.0);
+        assert_eq!(sprite.penetration(10.0, 10.0, &lower), Some((Side::Right, 1.0)));
+    }
+
+    #[test]
+    fn test_penetration_uses_solid_cells_only() {
+        let sprite = bird();
+
+        // A floor block under the bird: only the '|::|' row reaches it
+        let floor = Aabb::new(0.0, 11.0, 40.0, 10.0);
+        assert_eq!(sprite.penetration(10.0, 10.0, &floor), Some((Side::Top, 1.0)));
+
+        // A block arriving from the right hits the beak first
+        let wall = Aabb::new(14.0, 0.0, 3.0, 20.0);
+        assert_eq!(sprite.penetration(10.0, 10.0, &wall), Some((Side::Left, 1.0)));
+    }
+}
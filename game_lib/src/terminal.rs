@@ -0,0 +1,227 @@
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    queue,
+    style::{self, PrintStyledContent, Stylize},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use std::io::{stdout, Result, Stdout, Write};
+use std::time::Duration;
+
+use crate::input::{Action, InputSource};
+use crate::render::{Color, Renderer};
+
+impl From<Color> for style::Color {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => style::Color::Black,
+            Color::DarkGrey => style::Color::DarkGrey,
+            Color::Red => style::Color::Red,
+            Color::DarkRed => style::Color::DarkRed,
+            Color::Green => style::Color::Green,
+            Color::DarkGreen => style::Color::DarkGreen,
+            Color::Yellow => style::Color::Yellow,
+            Color::DarkYellow => style::Color::DarkYellow,
+            Color::Blue => style::Color::Blue,
+            Color::DarkBlue => style::Color::DarkBlue,
+            Color::Magenta => style::Color::Magenta,
+            Color::DarkMagenta => style::Color::DarkMagenta,
+            Color::Cyan => style::Color::Cyan,
+            Color::DarkCyan => style::Color::DarkCyan,
+            Color::White => style::Color::White,
+            Color::Grey => style::Color::Grey,
+            Color::Rgb { r, g, b } => style::Color::Rgb { r, g, b },
+        }
+    }
+}
+
+/// Renderer that draws to a terminal through crossterm
+///
+/// Drawing commands are queued and only written out on
+/// [`present`](Renderer::present).
+///
+/// Once [`init`](CrosstermRenderer::init) has set the terminal up, dropping
+/// the renderer restores it, so an error or panic never leaves the terminal
+/// in the alternate screen with the cursor hidden.
+pub struct CrosstermRenderer<W: Write = Stdout> {
+    out: W,
+    active: bool,
+}
+
+impl CrosstermRenderer {
+    /// Creates a renderer writing to stdout
+    pub fn new() -> Self {
+        Self::with_writer(stdout())
+    }
+}
+
+impl Default for CrosstermRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> CrosstermRenderer<W> {
+    /// Creates a renderer writing to any output stream
+    pub fn with_writer(out: W) -> Self {
+        Self { out, active: false }
+    }
+
+    /// Sets up the terminal for game display
+    ///
+    /// Enters alternate screen mode and hides the cursor
+    pub fn init(&mut self) -> Result<()> {
+        self.active = true;
+        self.out.execute(EnterAlternateScreen)?;
+        self.out.execute(Hide)?;
+        Ok(())
+    }
+
+    /// Restores terminal to original state
+    ///
+    /// Shows cursor and leaves alternate screen mode. Does nothing unless
+    /// the terminal was set up with [`init`](CrosstermRenderer::init).
+    pub fn cleanup(&mut self) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+        self.out.execute(Show)?;
+        self.out.execute(LeaveAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for CrosstermRenderer<W> {
+    fn drop(&mut self) {
+        // Nothing useful can be done about an error this late
+        let _ = self.cleanup();
+    }
+}
+
+impl<W: Write> Renderer for CrosstermRenderer<W> {
+    fn size(&self) -> Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        queue!(self.out, Clear(ClearType::All))
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, ch: char, color: Color) -> Result<()> {
+        queue!(
+            self.out,
+            MoveTo(x, y),
+            PrintStyledContent(ch.with(color.into()))
+        )
+    }
+
+    fn draw_text(&mut self, x: u16, y: u16, text: &str, color: Color) -> Result<()> {
+        queue!(
+            self.out,
+            MoveTo(x, y),
+            PrintStyledContent(text.with(color.into()))
+        )
+    }
+
+    fn present(&mut self) -> Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Maps a key to an action
+pub fn action_for_key(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Char('i') | KeyCode::Char(' ') => Some(Action::Jump),
+        KeyCode::Char('r') => Some(Action::Restart),
+        KeyCode::Char('p') => Some(Action::Pause),
+        KeyCode::Char('l') => Some(Action::Leaderboard),
+        KeyCode::Char('n') => Some(Action::Step),
+        KeyCode::Char('f') => Some(Action::FastForward),
+        KeyCode::Esc => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+/// Reads key presses from the terminal through crossterm
+///
+/// Resizes of the terminal arrive as [`Action::Resize`].
+#[derive(Debug, Default)]
+pub struct KeyboardInput;
+
+impl KeyboardInput {
+    /// Creates a keyboard source
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl InputSource for KeyboardInput {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> Result<()> {
+        while event::poll(Duration::ZERO)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => actions.extend(action_for_key(key.code)),
+                Event::Resize(width, height) => actions.push(Action::Resize(width, height)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_is_queued_until_present() {
+        let mut out = Vec::new();
+        let mut renderer = CrosstermRenderer::with_writer(&mut out);
+        renderer.draw_cell(3, 4, '*', Color::Blue).unwrap();
+        renderer.draw_text(0, 0, "Score", Color::Green).unwrap();
+        renderer.present().unwrap();
+        drop(renderer);
+
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.contains("\u{1b}[5;4H"));
+        assert!(written.contains('*'));
+        assert!(written.contains("Score"));
+    }
+
+    #[test]
+    fn test_drop_restores_terminal() {
+        let mut out = Vec::new();
+        CrosstermRenderer::with_writer(&mut out).init().unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert!(written.ends_with("\u{1b}[?25h\u{1b}[?1049l"));
+
+        // Restored only once, and never if it was not set up
+        let mut out = Vec::new();
+        let mut renderer = CrosstermRenderer::with_writer(&mut out);
+        renderer.cleanup().unwrap();
+        drop(renderer);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_color_conversion() {
+        assert_eq!(style::Color::from(Color::DarkCyan), style::Color::DarkCyan);
+        assert_eq!(
+            style::Color::from(Color::Rgb { r: 1, g: 2, b: 3 }),
+            style::Color::Rgb { r: 1, g: 2, b: 3 }
+        );
+    }
+
+    #[test]
+    fn test_key_mapping() {
+        assert_eq!(action_for_key(KeyCode::Char('i')), Some(Action::Jump));
+        assert_eq!(action_for_key(KeyCode::Char(' ')), Some(Action::Jump));
+        assert_eq!(action_for_key(KeyCode::Char('r')), Some(Action::Restart));
+        assert_eq!(action_for_key(KeyCode::Char('l')), Some(Action::Leaderboard));
+        assert_eq!(action_for_key(KeyCode::Char('n')), Some(Action::Step));
+        assert_eq!(action_for_key(KeyCode::Char('f')), Some(Action::FastForward));
+        assert_eq!(action_for_key(KeyCode::Esc), Some(Action::Quit));
+        assert_eq!(action_for_key(KeyCode::Char('x')), None);
+    }
+}
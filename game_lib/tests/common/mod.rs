@@ -0,0 +1,90 @@
+//! Helpers shared by the integration tests
+
+// Each test binary only uses some of them
+#![allow(dead_code)]
+
+use game_lib::{
+    animation::Animation,
+    clock::{Clock, ManualClock},
+    config::GameConfig,
+    game::GameSession,
+    headless::TextRenderer,
+    rect::Anchor,
+    render::Renderer,
+    scene::{draw_scene_at, Theme},
+    RECT_INTERVAL,
+};
+use std::time::Duration;
+
+/// Creates a game driven by a manual clock, with a handle to that clock
+pub fn new_game(screen_size: (u16, u16), config: GameConfig) -> (ManualClock, GameSession, Animation<ManualClock>) {
+    let clock = ManualClock::new();
+    let animation = Animation::with_clock(screen_size, config, clock.clone());
+    (clock, GameSession::new(), animation)
+}
+
+/// Searches for a seed whose first obstacle is a lone block hanging from
+/// the ceiling and returns the game right after that block spawned
+pub fn new_ceiling_block_game(
+    screen_size: (u16, u16),
+    config: GameConfig,
+) -> (ManualClock, GameSession, Animation<ManualClock>) {
+    (0..100)
+        .map(|seed| {
+            let (clock, mut game_session, mut animation) = new_game(screen_size, config);
+            animation.restart_with_seed(seed, &mut game_session);
+            clock.advance(Duration::from_millis(RECT_INTERVAL));
+            animation.update(&mut game_session);
+            (clock, game_session, animation)
+        })
+        .find(|(_, _, animation)| {
+            let rects = animation.get_rectangles();
+            rects.len() == 1 && rects[0].get_anchor() == Anchor::Top
+        })
+        .expect("no seed starts with a ceiling block")
+}
+
+/// Renders the current state of a game into text rows
+pub fn render_frame<C: Clock>(animation: &Animation<C>, game_session: &GameSession, size: (u16, u16)) -> Vec<String> {
+    render_frame_at(animation, game_session, size, 1.0)
+}
+
+/// Renders a game a fraction `alpha` of the way through its last update
+pub fn render_frame_at<C: Clock>(
+    animation: &Animation<C>,
+    game_session: &GameSession,
+    size: (u16, u16),
+    alpha: f32,
+) -> Vec<String> {
+    let mut renderer = TextRenderer::new(size);
+    draw_scene_at(&mut renderer, animation, game_session, &Theme::default(), alpha).unwrap();
+    renderer.present().unwrap();
+    renderer.lines()
+}
+
+/// Compares a frame against a golden snapshot
+///
+/// The snapshot is written as an indented raw string with one row per
+/// line wrapped in `|` so trailing spaces stay visible.
+#[track_caller]
+pub fn assert_frame(frame: &[String], snapshot: &str) {
+    let expected: Vec<&str> = snapshot
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.strip_prefix('|')
+                .and_then(|line| line.strip_suffix('|'))
+                .expect("snapshot rows must be wrapped in '|'")
+        })
+        .collect();
+
+    if frame != expected.as_slice() {
+        let actual: Vec<String> = frame.iter().map(|row| format!("|{}|", row)).collect();
+        panic!(
+            "frame does not match snapshot\n--- actual ---\n{}\n--- expected ---\n{}",
+            actual.join("\n"),
+            expected.iter().map(|row| format!("|{}|", row)).collect::<Vec<_>>().join("\n"),
+        );
+    }
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::{assert_frame, render_frame, render_frame_at};
+use game_lib::{
+    animation::Animation,
+    clock::ManualClock,
+    config::GameConfig,
+    game::GameSession,
+    headless::TextRenderer,
+    highscore::{HighScore, HighScores},
+    render::Renderer,
+    scene::{draw_leaderboard, Theme},
+    RECT_INTERVAL,
+};
+use std::time::Duration;
+
+const SCREEN: (u16, u16) = (40, 16);
+const SEED: u64 = 3;
+
+fn new_game() -> (ManualClock, GameSession, Animation<ManualClock>) {
+    let (clock, mut game_session, mut animation) = common::new_game(SCREEN, GameConfig::default());
+    animation.restart_with_seed(SEED, &mut game_session);
+    (clock, game_session, animation)
+}
+
+const START_FRAME: &str = r#"
+    |****************************************|
+    |* Level: 1                             *|
+    |* Score: 0                             *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |*                           |>         *|
+    |*                        |::|          *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |*                                      *|
+    |****************************************|
+"#;
+
+#[test]
+fn test_start_frame() {
+    let (_, game_session, animation) = new_game();
+    assert_frame(&render_frame(&animation, &game_session, SCREEN), START_FRAME);
+}
+
+#[test]
+fn test_interpolated_frames_blend_the_last_update() {
+    let (_, mut game_session, mut animation) = new_game();
+    animation.update(&mut game_session);
+    animation.update(&mut game_session);
+    let before = render_frame(&animation, &game_session, SCREEN);
+
+    // The bird falls from row 10 to 12.5
+    animation.update(&mut game_session);
+    let after = render_frame(&animation, &game_session, SCREEN);
+    assert_ne!(before, after);
+    assert_eq!(render_frame_at(&animation, &game_session, SCREEN, 0.0), before);
+
+    let halfway = render_frame_at(&animation, &game_session, SCREEN, 0.5);
+    assert_ne!(halfway, before);
+    assert_ne!(halfway, after);
+}
+
+#[test]
+fn test_collision_game_over_frame() {
+    let (clock, mut game_session, mut animation) = new_game();
+
+    clock.advance(Duration::from_millis(RECT_INTERVAL));
+    while !animation.is_game_over() {
+        animation.update(&mut game_session);
+    }
+
+    assert_frame(
+        &render_frame(&animation, &game_session, SCREEN),
+        r#"
+        |****************************************|
+        |* Level: 1                             *|
+        |* Score: 0                             *|
+        |*                                      *|
+        |*                                      *|
+        |*                                      *|
+        |*      Crashed into a floor block      *|
+        |*                                      *|
+        |*     GAME OVER! Press ESC to quit     *|
+        |*                                      *|
+        |*          Press R to restart          *|
+        |*                                      *|
+        |*               Seed: 3     |>******** *|
+        |*                        |::|********* *|
+        |*                            ********* *|
+        |****************************************|
+        "#,
+    );
+}
+
+#[test]
+fn test_restart_frame() {
+    let (clock, mut game_session, mut animation) = new_game();
+
+    clock.advance(Duration::from_millis(RECT_INTERVAL));
+    while !animation.is_game_over() {
+        animation.update(&mut game_session);
+    }
+    animation.restart_with_seed(SEED, &mut game_session);
+
+    assert_frame(&render_frame(&animation, &game_session, SCREEN), START_FRAME);
+}
+
+#[test]
+fn test_leaderboard_frame() {
+    let mut scores = HighScores::new(3);
+    for (name, score, seed) in [("Ann", 42, 7), ("Bob", 17, 12345678901234)] {
+        scores.insert(HighScore {
+            name: name.to_string(),
+            score,
+            level: 3,
+            date: 1_700_000_000,
+            seed,
+        });
+    }
+
+    let mut renderer = TextRenderer::new((50, 9));
+    draw_leaderboard(&mut renderer, &scores, Some(1), &Theme::default()).unwrap();
+    renderer.present().unwrap();
+
+    assert_frame(
+        &renderer.lines(),
+        r#"
+        |**************************************************|
+        |*                                                *|
+        |*                  HIGH SCORES                   *|
+        |*                                                *|
+        |*  #  Name          Score Lvl  Date        Seed  *|
+        |*  1. Ann              42   3  2023-11-14  7     *|
+        |*  2. Bob              17   3  2023-11-14  12345 *|
+        |*                                                *|
+        |**************************************************|
+        "#,
+    );
+}
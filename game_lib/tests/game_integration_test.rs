@@ -1,16 +1,21 @@
+mod common;
+
+use common::{new_ceiling_block_game, new_game};
 use game_lib::{
-    animation::Animation,
-    game::GameSession,
+    animation::{Animation, MIN_SCREEN_SIZE},
+    clock::ManualClock,
+    collision::Side,
+    config::GameConfig,
+    game::{GameSession, GameStatus},
+    rect::Anchor,
     RECT_INTERVAL,
 };
-use std::{thread, time::Duration};
+use std::time::Duration;
 
 #[test]
 fn test_game_flow() {
     // Set up game components
-    let screen_size = (80, 24);
-    let mut game_session = GameSession::new();
-    let mut animation = Animation::new(screen_size);
+    let (clock, mut game_session, mut animation) = new_game((80, 24), GameConfig::default());
     
     // Start game
     game_session.start();
@@ -23,7 +28,7 @@ fn test_game_flow() {
     // Run a few game cycles
     for _ in 0..5 {
         animation.update(&mut game_session);
-        thread::sleep(Duration::from_millis(RECT_INTERVAL));
+        clock.advance(Duration::from_millis(RECT_INTERVAL));
     }
     
     // Verify obstacles are being generated
@@ -39,7 +44,7 @@ fn test_game_flow() {
     let initial_level = game_session.get_level();
     for _ in 0..10 {
         animation.update(&mut game_session);
-        thread::sleep(Duration::from_millis(50));
+        clock.advance(Duration::from_millis(50));
     }
     assert!(game_session.get_level() >= initial_level);
     
@@ -52,15 +57,13 @@ fn test_game_flow() {
 
 #[test]
 fn test_collision_mechanics() {
-    let screen_size = (80, 24);
-    let mut game_session = GameSession::new();
-    let mut animation = Animation::new(screen_size);
-    game_session.start();
+    let (clock, mut game_session, mut animation) = new_game((80, 24), GameConfig::default());
+    animation.restart_with_seed(7, &mut game_session);
     
     // Let some obstacles generate
     for _ in 0..3 {
         animation.update(&mut game_session);
-        thread::sleep(Duration::from_millis(RECT_INTERVAL));
+        clock.advance(Duration::from_millis(RECT_INTERVAL));
     }
     
     // Verify game continues while no collision
@@ -72,6 +75,8 @@ fn test_collision_mechanics() {
     let mut attempts = 0;
     while !animation.is_game_over() && attempts < max_attempts {
         animation.update(&mut game_session);
+        // Keep spawning: ceiling blocks alone never reach a bird resting on the floor
+        clock.advance(Duration::from_millis(RECT_INTERVAL / 4));
         attempts += 1;
     }
 
@@ -81,9 +86,7 @@ fn test_collision_mechanics() {
 
 #[test]
 fn test_game_speed_progression() {
-    let screen_size = (80, 24);
-    let mut game_session = GameSession::new();
-    let mut animation = Animation::new(screen_size);
+    let (_, mut game_session, mut animation) = new_game((80, 24), GameConfig::default());
     game_session.start();
     
     // Record initial state
@@ -101,4 +104,182 @@ fn test_game_speed_progression() {
     // Game should still be running after level increases
     assert!(!animation.is_game_over());
     assert!(game_session.is_running());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_manual_clock_spawn_timing() {
+    let (clock, mut game_session, mut animation) = new_game((80, 24), GameConfig::default());
+    game_session.start();
+
+    // No obstacle until a full interval has elapsed
+    clock.advance(Duration::from_millis(RECT_INTERVAL - 1));
+    animation.update(&mut game_session);
+    assert!(animation.get_rectangles().is_empty());
+
+    clock.advance(Duration::from_millis(1));
+    animation.update(&mut game_session);
+    assert!(!animation.get_rectangles().is_empty());
+    // Spawning alone is not worth anything
+    assert_eq!(game_session.get_score(), 0);
+
+    // Thousands of frames with no elapsed time never spawn anything
+    for _ in 0..5000 {
+        if animation.is_game_over() {
+            break;
+        }
+        animation.update(&mut game_session);
+    }
+    assert!(animation.get_rectangles().iter().all(|rect| rect.get_obstacle() == 1));
+}
+
+#[test]
+fn test_score_when_passing_obstacle() {
+    // A wide close-call margin turns the same pass into a bonus
+    for (distance, expected) in [(0, 1), (20, 3)] {
+        let mut config = GameConfig::default();
+        config.score.close_call_distance = distance;
+
+        // A lone ceiling block passes over the bird resting on the floor
+        let (_, mut game_session, mut animation) = new_ceiling_block_game((80, 24), config);
+
+        while let Some(rect) = animation.get_rectangles().first() {
+            let passed = rect.get_bounds().right() <= animation.get_bird().get_bounds().left();
+            assert_eq!(game_session.get_passed(), passed as u32);
+            animation.update(&mut game_session);
+            assert!(!animation.is_game_over());
+        }
+        assert_eq!(game_session.get_passed(), 1);
+        assert_eq!(game_session.get_score(), expected);
+        assert_eq!(animation.get_combo(), (expected > 1) as u32);
+    }
+}
+
+#[test]
+fn test_same_seed_replays_same_run() {
+    let screen_size = (80, 24);
+    let clock = ManualClock::new();
+    let mut first_session = GameSession::new();
+    let mut second_session = GameSession::new();
+    let mut first = Animation::with_clock(screen_size, GameConfig::default(), clock.clone());
+    let mut second = Animation::with_clock(screen_size, GameConfig::default(), clock.clone());
+    first.restart_with_seed(99, &mut first_session);
+    second.restart_with_seed(99, &mut second_session);
+    assert_eq!(first_session.get_seed(), 99);
+
+    for _ in 0..40 {
+        clock.advance(Duration::from_millis(RECT_INTERVAL / 4));
+        first.update(&mut first_session);
+        second.update(&mut second_session);
+        assert_eq!(first.get_rectangles(), second.get_rectangles());
+    }
+    assert_eq!(first_session.get_score(), second_session.get_score());
+}
+
+#[test]
+fn test_fractional_scroll_speed() {
+    let mut config = GameConfig::default();
+    config.difficulty.start.scroll_speed = 0.5;
+    let (clock, mut game_session, mut animation) = new_game((80, 24), config);
+    game_session.start();
+
+    clock.advance(Duration::from_millis(RECT_INTERVAL));
+    animation.update(&mut game_session);
+    let start = animation.get_rectangles()[0].get_x();
+
+    // Half a cell per update: one column every two updates
+    for _ in 0..4 {
+        animation.update(&mut game_session);
+    }
+    let rect = &animation.get_rectangles()[0];
+    assert_eq!(rect.get_x(), start - 2.0);
+    assert_eq!(rect.get_leftmost_x(), start as u16 - 2);
+}
+
+#[test]
+fn test_ceiling_obstacle_ends_run() {
+    let (_, mut game_session, mut animation) = new_ceiling_block_game((80, 24), GameConfig::default());
+
+    // Hugging the ceiling is no longer safe
+    while !animation.is_game_over() {
+        animation.jump();
+        animation.update(&mut game_session);
+        assert!(!animation.get_rectangles().is_empty());
+    }
+    let rect = &animation.get_rectangles()[0];
+    assert!(animation.get_bird().get_top_y() <= rect.get_bottom_y());
+
+    // The block scrolled into the bird rather than the bird rising into it
+    let contact = animation.get_contact().unwrap();
+    assert_eq!(contact.entity, 0);
+    assert_eq!(contact.side, Side::Left);
+    assert!(contact.penetration > 0.0);
+
+    animation.restart(&mut game_session);
+    assert!(animation.get_contact().is_none());
+}
+
+#[test]
+fn test_countdown_holds_world() {
+    let (clock, _, mut animation) = new_game((80, 24), GameConfig::default());
+    let mut game_session = GameSession::with_countdown(Duration::from_millis(3 * RECT_INTERVAL));
+    animation.restart_with_seed(1, &mut game_session);
+    let start = animation.get_bird().get_position();
+
+    // Nothing moves or spawns while counting down
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(RECT_INTERVAL));
+        animation.update(&mut game_session);
+        assert!(animation.get_rectangles().is_empty());
+        assert_eq!(animation.get_bird().get_position(), start);
+    }
+    assert_eq!(game_session.get_status(), GameStatus::Running);
+
+    // The spawn timer starts with the run, not before it
+    clock.advance(Duration::from_millis(RECT_INTERVAL - 1));
+    animation.update(&mut game_session);
+    assert!(animation.get_rectangles().is_empty());
+    clock.advance(Duration::from_millis(1));
+    animation.update(&mut game_session);
+    assert!(!animation.get_rectangles().is_empty());
+
+    // A crash ends the session's run
+    while !animation.is_game_over() {
+        animation.update(&mut game_session);
+    }
+    assert_eq!(game_session.get_status(), GameStatus::GameOver);
+}
+
+#[test]
+fn test_resize_during_run() {
+    let (clock, mut game_session, mut animation) = new_game((80, 24), GameConfig::default());
+    animation.restart_with_seed(4, &mut game_session);
+    for _ in 0..2 {
+        clock.advance(Duration::from_millis(RECT_INTERVAL));
+        animation.update(&mut game_session);
+    }
+    let (_, y) = animation.get_bird().get_position();
+
+    // Obstacles stay on their edge and everything keeps its place in height
+    animation.resize((100, 36));
+    assert_eq!(animation.get_screen_size(), (100, 36));
+    assert_eq!(animation.get_bird().get_position().1, y * 1.5);
+    for rect in animation.get_rectangles() {
+        match rect.get_anchor() {
+            Anchor::Top => assert_eq!(rect.get_top_y(), 0),
+            Anchor::Bottom => assert_eq!(rect.get_bottom_y(), 35),
+        }
+    }
+
+    // New obstacles spawn at the new right edge
+    let spawned = animation.get_rectangles().len();
+    clock.advance(Duration::from_millis(RECT_INTERVAL));
+    animation.update(&mut game_session);
+    let rects = animation.get_rectangles();
+    assert!(rects.len() > spawned);
+    assert_eq!(rects.last().unwrap().get_rightmost_x(), 99);
+
+    // A tiny terminal only shows part of the smallest world
+    animation.resize((10, 5));
+    assert_eq!(animation.get_screen_size(), MIN_SCREEN_SIZE);
+    assert!(animation.get_bird().get_bottom_y() < MIN_SCREEN_SIZE.1);
+}
@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+
+use crate::controller::Protocol;
+
+/// Environment variable used when `--port` is not given
+pub const PORT_ENV: &str = "PLATFORM_GAME_PORT";
+
+/// Environment variable used when `--baud` is not given
+pub const BAUD_ENV: &str = "PLATFORM_GAME_BAUD";
+
+/// Command line options of the game
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    /// Path passed with `--config <file>`
+    pub config: Option<PathBuf>,
+    /// Seed passed with `--seed <n>`
+    pub seed: Option<u64>,
+    /// Serial port passed with `--port <path>`
+    pub port: Option<String>,
+    /// Baud rate passed with `--baud <n>`
+    pub baud: Option<u32>,
+    /// Controller protocol passed with `--protocol <framed|bytes>`, bytes when unset
+    pub protocol: Option<Protocol>,
+    /// Where to save the session's replay, passed with `--record <file>`
+    pub record: Option<PathBuf>,
+    /// Replay to watch instead of playing, passed with `--replay <file>`
+    pub replay: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses the process's command line and environment
+    pub fn parse() -> Result<Self, String> {
+        let mut args = Self::parse_from(std::env::args().skip(1))?;
+        args.apply_env(|key| std::env::var(key).ok())?;
+        Ok(args)
+    }
+
+    /// Fills options not given on the command line from environment variables
+    pub fn apply_env<F>(&mut self, var: F) -> Result<(), String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if self.port.is_none() {
+            self.port = var(PORT_ENV);
+        }
+        if self.baud.is_none() {
+            if let Some(value) = var(BAUD_ENV) {
+                self.baud = Some(parse_baud(&value)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses options from an argument list without the program name
+    pub fn parse_from<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    parsed.config = Some(PathBuf::from(value_of(&arg, args.next())?));
+                }
+                "--seed" => {
+                    let value = value_of(&arg, args.next())?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    parsed.seed = Some(seed);
+                }
+                "--port" => {
+                    parsed.port = Some(value_of(&arg, args.next())?);
+                }
+                "--baud" => {
+                    parsed.baud = Some(parse_baud(&value_of(&arg, args.next())?)?);
+                }
+                "--protocol" => {
+                    parsed.protocol = Some(value_of(&arg, args.next())?.parse()?);
+                }
+                "--record" => {
+                    parsed.record = Some(PathBuf::from(value_of(&arg, args.next())?));
+                }
+                "--replay" => {
+                    parsed.replay = Some(PathBuf::from(value_of(&arg, args.next())?));
+                }
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn value_of(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("missing value for {}", flag))
+}
+
+fn parse_baud(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(baud) if baud > 0 => Ok(baud),
+        _ => Err(format!("invalid baud rate '{}'", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse_from(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        let args = parse(&["--config", "game.toml", "--seed", "42", "--port", "/dev/ttyUSB0", "--baud", "9600"]).unwrap();
+        assert_eq!(args.config, Some(PathBuf::from("game.toml")));
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.port.as_deref(), Some("/dev/ttyUSB0"));
+        assert_eq!(args.baud, Some(9600));
+
+        let args = parse(&["--protocol", "bytes"]).unwrap();
+        assert_eq!(args.protocol, Some(Protocol::Bytes));
+
+        let args = parse(&["--record", "run.replay", "--replay", "old.replay"]).unwrap();
+        assert_eq!(args.record, Some(PathBuf::from("run.replay")));
+        assert_eq!(args.replay, Some(PathBuf::from("old.replay")));
+
+        assert_eq!(parse(&[]).unwrap(), Args::default());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--seed", "abc"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["--baud", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_env_fallback() {
+        let env = |key: &str| match key {
+            PORT_ENV => Some("COM5".to_string()),
+            BAUD_ENV => Some("57600".to_string()),
+            _ => None,
+        };
+
+        let mut args = parse(&[]).unwrap();
+        args.apply_env(env).unwrap();
+        assert_eq!(args.port.as_deref(), Some("COM5"));
+        assert_eq!(args.baud, Some(57600));
+
+        // Flags win over the environment
+        let mut args = parse(&["--port", "/dev/ttyACM0"]).unwrap();
+        args.apply_env(env).unwrap();
+        assert_eq!(args.port.as_deref(), Some("/dev/ttyACM0"));
+    }
+}
@@ -0,0 +1,274 @@
+use std::io::{self, ErrorKind};
+use std::time::{Duration, Instant};
+
+use game_lib::input::{Action, InputSource, SerialInput};
+use game_lib::protocol::FramedInput;
+use serialport::{SerialPort, SerialPortType};
+
+/// Baud rate used when none is given
+pub const DEFAULT_BAUD: u32 = 115200;
+
+/// How long to wait between attempts to (re)open the controller
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wire format spoken by the controller
+///
+/// Defaults to [`Protocol::Bytes`], which every controller built before the
+/// framed protocol speaks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Protocol {
+    /// Framed messages with a version handshake, see [`game_lib::protocol`]
+    Framed,
+    /// Legacy firmware sending a bare `i` or `j` byte per jump
+    #[default]
+    Bytes,
+}
+
+impl std::str::FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        match src {
+            "framed" => Ok(Protocol::Framed),
+            "bytes" => Ok(Protocol::Bytes),
+            _ => Err(format!("unknown protocol '{}', expected 'framed' or 'bytes'", src)),
+        }
+    }
+}
+
+/// Where to find the serial controller and how to talk to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortConfig {
+    /// Port path such as `/dev/ttyUSB0` or `COM5`; auto-detected when `None`
+    pub path: Option<String>,
+    pub baud: u32,
+    pub protocol: Protocol,
+}
+
+impl PortConfig {
+    /// Opens the configured port, or the first USB serial port if no path is set
+    pub fn open(&self) -> Option<Box<dyn SerialPort>> {
+        let path = self.path.clone().or_else(detect_port)?;
+        serialport::new(path, self.baud)
+            .timeout(Duration::from_millis(10))
+            .open()
+            .ok()
+    }
+
+    /// Opens the port and wraps it in an input source for the configured protocol
+    pub fn connect(&self) -> Option<Box<dyn InputSource>> {
+        let port = self.open()?;
+        match self.protocol {
+            Protocol::Framed => Some(Box::new(FramedInput::new(port).ok()?)),
+            Protocol::Bytes => Some(Box::new(SerialInput::new(port))),
+        }
+    }
+}
+
+/// Returns the name of the first USB serial port, if any
+///
+/// Built-in UARTs such as `/dev/ttyS0` are always listed on Linux, so only
+/// USB devices are considered.
+fn detect_port() -> Option<String> {
+    serialport::available_ports()
+        .ok()?
+        .into_iter()
+        .find(|port| matches!(port.port_type, SerialPortType::UsbPort(_)))
+        .map(|port| port.port_name)
+}
+
+/// Serial controller that survives being unplugged
+///
+/// While no device is open the controller produces no actions and tries to
+/// open one again every [`RETRY_INTERVAL`]. Read errors close the device
+/// instead of being reported, so the keyboard keeps working regardless.
+///
+/// A device that sends data the input source rejects, such as a framed
+/// hello with another protocol version, would fail the same way every time;
+/// it is given up on and the error is kept for [`Controller::get_error`].
+pub struct Controller<S: InputSource, F: FnMut() -> Option<S>> {
+    open: F,
+    input: Option<S>,
+    last_attempt: Instant,
+    retry_interval: Duration,
+    ever_connected: bool,
+    error: Option<io::Error>,
+}
+
+impl<S: InputSource, F: FnMut() -> Option<S>> Controller<S, F> {
+    /// Creates a controller and makes a first attempt to open the device
+    pub fn new(open: F, retry_interval: Duration) -> Self {
+        let mut controller = Self {
+            open,
+            input: None,
+            last_attempt: Instant::now(),
+            retry_interval,
+            ever_connected: false,
+            error: None,
+        };
+        controller.connect();
+        controller
+    }
+
+    /// Returns `true` while a device is open
+    pub fn is_connected(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Returns `true` if a device was open at some point
+    pub fn was_connected(&self) -> bool {
+        self.ever_connected
+    }
+
+    /// Returns why the device was given up on, if it was
+    pub fn get_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    fn connect(&mut self) {
+        self.last_attempt = Instant::now();
+        if let Some(input) = (self.open)() {
+            self.input = Some(input);
+            self.ever_connected = true;
+        }
+    }
+}
+
+impl<S: InputSource, F: FnMut() -> Option<S>> InputSource for Controller<S, F> {
+    fn poll(&mut self, actions: &mut Vec<Action>) -> io::Result<()> {
+        let retry = self.error.is_none() && self.last_attempt.elapsed() >= self.retry_interval;
+        if self.input.is_none() && retry {
+            self.connect();
+        }
+
+        if let Some(input) = &mut self.input {
+            match input.poll(actions) {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::InvalidData => {
+                    self.input = None;
+                    self.error = Some(err);
+                }
+                Err(_) => {
+                    // Device was unplugged or failed; try again later
+                    self.input = None;
+                    self.last_attempt = Instant::now();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::io::{Cursor, ErrorKind, Read};
+
+    /// Yields its bytes once, then fails as if unplugged
+    struct Unplugged(Cursor<Vec<u8>>);
+
+    impl Read for Unplugged {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.read(buf)? {
+                0 => Err(io::Error::from(ErrorKind::BrokenPipe)),
+                count => Ok(count),
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_device_is_keyboard_only() {
+        let mut controller = Controller::new(|| None::<SerialInput<Unplugged>>, Duration::ZERO);
+        let mut actions = Vec::new();
+
+        assert!(controller.poll(&mut actions).is_ok());
+        assert!(actions.is_empty());
+        assert!(!controller.is_connected());
+        assert!(!controller.was_connected());
+    }
+
+    #[test]
+    fn test_reconnects_after_unplug() {
+        let mut devices: VecDeque<Option<Unplugged>> = VecDeque::from(vec![
+            Some(Unplugged(Cursor::new(b"i".to_vec()))),
+            None,
+            Some(Unplugged(Cursor::new(b"j".to_vec()))),
+        ]);
+        let mut controller = Controller::new(
+            move || devices.pop_front().flatten().map(SerialInput::new),
+            Duration::ZERO,
+        );
+        let mut actions = Vec::new();
+
+        controller.poll(&mut actions).unwrap();
+        assert_eq!(actions, vec![Action::Jump]);
+
+        // Unplugged: the read error disconnects without surfacing
+        controller.poll(&mut actions).unwrap();
+        assert!(!controller.is_connected());
+        assert!(controller.was_connected());
+
+        // First retry finds nothing, the second finds the device again
+        controller.poll(&mut actions).unwrap();
+        assert!(!controller.is_connected());
+        controller.poll(&mut actions).unwrap();
+        assert!(controller.is_connected());
+        assert_eq!(actions, vec![Action::Jump, Action::Jump]);
+    }
+
+    #[test]
+    fn test_retry_interval_is_respected() {
+        let mut attempts = 0;
+        let mut controller = Controller::new(
+            || {
+                attempts += 1;
+                None::<SerialInput<Unplugged>>
+            },
+            Duration::from_secs(60),
+        );
+        controller.poll(&mut Vec::new()).unwrap();
+        controller.poll(&mut Vec::new()).unwrap();
+        drop(controller);
+        assert_eq!(attempts, 1);
+    }
+
+    /// Speaks a protocol the game does not understand
+    struct Mismatched;
+
+    impl InputSource for Mismatched {
+        fn poll(&mut self, _actions: &mut Vec<Action>) -> io::Result<()> {
+            Err(io::Error::new(ErrorKind::InvalidData, "controller speaks protocol version 2, expected 1"))
+        }
+    }
+
+    #[test]
+    fn test_gives_up_on_mismatched_device() {
+        let mut attempts = 0;
+        let mut controller = Controller::new(
+            || {
+                attempts += 1;
+                Some(Mismatched)
+            },
+            Duration::ZERO,
+        );
+        for _ in 0..3 {
+            controller.poll(&mut Vec::new()).unwrap();
+        }
+        assert!(!controller.is_connected());
+        assert_eq!(
+            controller.get_error().map(ToString::to_string).as_deref(),
+            Some("controller speaks protocol version 2, expected 1")
+        );
+        drop(controller);
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_parse_protocol() {
+        assert_eq!("framed".parse(), Ok(Protocol::Framed));
+        assert_eq!("bytes".parse(), Ok(Protocol::Bytes));
+        assert!("morse".parse::<Protocol>().is_err());
+        assert_eq!(Protocol::default(), Protocol::Bytes);
+    }
+}
@@ -1,147 +1,353 @@
-use std::io::{self, Write, Read};
-use crossterm::{
-    execute, queue,
-    style::{self, Stylize},
-    cursor,
-    terminal::{Clear, ClearType, size},
-    event::{self, Event, KeyCode, KeyEventKind},
-};
+use std::error::Error;
+use std::path::Path;
 use std::time::Duration;
 use game_lib::animation::Animation;
-use game_lib::game::GameSession;
+use game_lib::autopilot::Autopilot;
+use game_lib::clock::ManualClock;
+use game_lib::config::ConfigError;
+use game_lib::frame::FrameBuffer;
+use game_lib::game::{GameEvent, GameSession, GameStatus};
+use game_lib::game_loop::GameLoop;
+use game_lib::highscore::{self, HighScore, HighScores, DEFAULT_CAPACITY};
+use game_lib::input::{Action, InputSource};
+use game_lib::render::Renderer;
+use game_lib::replay::{self, Player, Replay, ReplayError, ReplayInput};
+use game_lib::scene::{draw_leaderboard, draw_scene_at};
+use game_lib::simulation::Simulation;
+use game_lib::terminal::{CrosstermRenderer, KeyboardInput};
 
-use serialport::{self};
+mod args;
+mod controller;
+mod name_entry;
+mod screens;
+mod settings;
+mod viewer;
 
+use args::Args;
+use controller::{Controller, PortConfig, DEFAULT_BAUD, RETRY_INTERVAL};
+use name_entry::{Entry, NameEntry};
+use screens::{draw_countdown, draw_leaderboard_hint, draw_name_prompt, draw_paused, draw_title};
+use settings::{Settings, SettingsWatcher};
 
-const DEFAULT_PORT: &str = "COM5";
+/// Time to get ready before a run starts or resumes
+const COUNTDOWN: Duration = Duration::from_secs(3);
 
-fn main() -> Result<(), serialport::Error> {
-    let mut stdout = io::stdout();
-    let screen_size = size().unwrap();
-    let (width, height) = screen_size;
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse()?;
+    let mut settings = match &args.config {
+        Some(path) => Settings::load(path)?,
+        None => Settings::default(),
+    };
+    let mut watcher = args.config.clone().map(SettingsWatcher::new);
+    let mut notice: Option<String> = None;
 
-    let mut game_session = GameSession::new();
-    game_session.init_terminal()?;
-    game_session.start();
+    // High scores live under the XDG data dir; without one they last a session
+    let mut scores_path = highscore::default_path("platform-game");
+    let mut scores = HighScores::default();
+    if let Some(path) = scores_path.clone() {
+        match HighScores::load(&path, DEFAULT_CAPACITY) {
+            Ok(loaded) => scores = loaded,
+            // Never overwrite a table that could not be read: move it aside,
+            // or keep this session's scores to itself
+            Err(err) => match highscore::set_aside(&path) {
+                Ok(backup) => notice = Some(format!("{}; kept it as {}", err, backup.display())),
+                Err(_) => {
+                    notice = Some(format!("{}; scores will not be saved", err));
+                    scores_path = None;
+                }
+            },
+        }
+    }
+
+    // Watching a replay needs neither the controller nor the high scores
+    if let Some(path) = &args.replay {
+        let mut player = Player::new(Replay::load(path)?)?;
+        player.animation_mut().set_obstacle_texture(settings.obstacle_texture.clone());
+        if let Some(sprite) = &settings.bird_sprite {
+            player.animation_mut().set_bird_sprite(sprite.clone());
+        }
+        let mut renderer = FrameBuffer::new(CrosstermRenderer::new())?;
+        renderer.backend_mut().init()?;
+        let result = viewer::run(&mut renderer, &mut player, &settings);
+        renderer.backend_mut().cleanup()?;
+        return result;
+    }
 
-    let mut animation = Animation::new(screen_size);
-    let mut update_interval = 40;
+    // The last session is kept so a confusing run can be watched again
+    let record_path = args.record.clone().or_else(|| replay::default_path("platform-game"));
+    let mut recording: Option<Replay> = None;
 
-   let mut current_level = game_session.get_level();
+    // Only cells that changed since the last frame reach the terminal
+    let mut renderer = FrameBuffer::new(CrosstermRenderer::new())?;
+    let mut screen_size = renderer.size()?;
 
-   let mut port = serialport::new(DEFAULT_PORT, 115200)
-        .timeout(Duration::from_millis(10))
-        .open()?;
+    let mut game_session = GameSession::with_countdown(COUNTDOWN);
+    // Dropping the renderer restores the terminal, also when an error ends the game
+    renderer.backend_mut().init()?;
 
-    let mut buffer: Vec<u8> = vec![0; 1];
+    // Game time only moves with simulation steps, so spawning follows the
+    // fixed timestep instead of the wall clock
+    let sim_clock = ManualClock::new();
+    let mut animation = new_animation(screen_size, &settings, sim_clock.clone())?;
+    let mut next_seed = args.seed;
+    let mut game_loop = GameLoop::new(
+        Duration::from_millis(settings.frame_interval),
+        Duration::from_millis(settings.render_interval),
+    );
 
-    while game_session.is_running() {
-        if event::poll(Duration::from_millis(0))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('i') => animation.jump(),
-                        KeyCode::Esc => break,
-                        KeyCode::Char('r') => {
-                            animation.restart(&mut game_session);
-                            update_interval = 40;
-                            current_level = game_session.get_level();
-                        },
-                        _ => {}
+    // The title screen shows the autopilot playing a demo run
+    let autopilot = Autopilot::new();
+    let mut demo = new_demo(screen_size, &settings, game_loop.get_step(), animation.get_seed())?;
+
+    // The keyboard always works; the serial controller is optional and may come and go
+    let port_config = PortConfig {
+        path: args.port.clone(),
+        baud: args.baud.unwrap_or(DEFAULT_BAUD),
+        protocol: args.protocol.unwrap_or_default(),
+    };
+    let mut keyboard = KeyboardInput::new();
+    let mut controller = Controller::new(move || port_config.connect(), RETRY_INTERVAL);
+    let mut actions = Vec::new();
+    let mut panel: Option<Panel> = None;
+    // Whether the current run's score has been offered to the table
+    let mut recorded = false;
+
+    'game: loop {
+        // Typing a name needs the raw keys instead of game actions
+        if let Some(Panel::NameEntry(entry)) = &mut panel {
+            match entry.poll(&mut actions)? {
+                Entry::Editing => {}
+                Entry::Done(name) => {
+                    let highlight = scores.insert(HighScore::new(
+                        &name,
+                        game_session.get_score(),
+                        game_session.get_level(),
+                        game_session.get_seed(),
+                    ));
+                    if let Some(path) = &scores_path {
+                        if let Err(err) = scores.save(path) {
+                            notice = Some(err.to_string());
+                        }
                     }
+                    panel = Some(Panel::Leaderboard { highlight });
                 }
+                Entry::Cancelled => panel = None,
             }
+        } else {
+            keyboard.poll(&mut actions)?;
         }
+        controller.poll(&mut actions)?;
 
-        match port.read(buffer.as_mut_slice()) {
-            Ok(t) => {
-                if t > 0 {
-                    let received_char = buffer[0] as char;
-                    match received_char {
-                        'i' => {
-                            animation.jump();
-                        }
-                        'j' => {
-                            animation.jump();
-                        }
-                        _ => {} // Ignore other characters
+        let mut start_run = false;
+        for action in actions.drain(..) {
+            match (&panel, game_session.get_status(), action) {
+                // Lay everything out again for the new size; the next frame
+                // redraws the whole screen
+                (_, _, Action::Resize(width, height)) => {
+                    screen_size = (width, height);
+                    renderer.resize(screen_size);
+                    animation.resize(screen_size);
+                    demo.animation_mut().resize(screen_size);
+                    record(&mut recording, ReplayInput::Resize(width, height));
+                }
+                (Some(Panel::Leaderboard { .. }), status, Action::Leaderboard | Action::Quit) => {
+                    panel = None;
+                    // A finished run has been seen through; go back to the title
+                    if status == GameStatus::GameOver {
+                        game_session.handle(GameEvent::ToTitle)?;
+                        record(&mut recording, ReplayInput::ToTitle);
                     }
                 }
+                (Some(_), _, _) => {}
+                (None, GameStatus::Title, Action::Jump) => start_run = true,
+                (None, GameStatus::Title | GameStatus::GameOver, Action::Leaderboard) => {
+                    panel = Some(Panel::Leaderboard { highlight: None });
+                }
+                (None, GameStatus::Paused, Action::Quit) => {
+                    game_session.handle(GameEvent::ToTitle)?;
+                    record(&mut recording, ReplayInput::ToTitle);
+                }
+                (None, _, Action::Quit) => break 'game,
+                (None, GameStatus::Running, Action::Jump) => {
+                    animation.jump();
+                    record(&mut recording, ReplayInput::Jump);
+                }
+                (None, GameStatus::Countdown | GameStatus::Running, Action::Pause) => {
+                    game_session.handle(GameEvent::Pause)?;
+                    record(&mut recording, ReplayInput::Pause);
+                }
+                (None, GameStatus::Paused, Action::Pause) => {
+                    game_session.handle(GameEvent::Resume)?;
+                    record(&mut recording, ReplayInput::Resume);
+                }
+                (None, status, Action::Restart) if status != GameStatus::Title => start_run = true,
+                _ => {}
             }
-            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (), // Ignore timeouts
-            Err(e) => eprintln!("Error reading from serial port: {}", e),
         }
 
-        execute!(stdout, Clear(ClearType::All))?;
+        if start_run {
+            // Pick up edits to the config file between runs
+            match watcher.as_mut().and_then(SettingsWatcher::poll) {
+                Some(Ok(reloaded)) => {
+                    settings = reloaded;
+                    animation = new_animation(screen_size, &settings, sim_clock.clone())?;
+                    game_loop = GameLoop::new(
+                        Duration::from_millis(settings.frame_interval),
+                        Duration::from_millis(settings.render_interval),
+                    );
+                    demo = new_demo(screen_size, &settings, game_loop.get_step(), demo.get_seed())?;
+                    // A replay has one config; the next run starts a new one
+                    notice = save_replay(recording.take().as_ref(), record_path.as_deref())
+                        .err()
+                        .map(|err| err.to_string());
+                }
+                Some(Err(err)) => notice = Some(err.to_string()),
+                None => {}
+            }
+            match next_seed.take() {
+                Some(seed) => animation.restart_with_seed(seed, &mut game_session),
+                None => animation.restart(&mut game_session),
+            }
+            let seed = animation.get_seed();
+            match &mut recording {
+                Some(replay) => replay.record(ReplayInput::Restart(seed)),
+                None => {
+                    let step = game_loop.get_step();
+                    recording = Some(Replay::new(seed, screen_size, step, COUNTDOWN, settings.game));
+                }
+            }
+            recorded = false;
+        }
 
-        // Draw border
-        for y in 0..height {
-            for x in 0..width {
-                if (y == 0 || y == height - 1) || (x == 0 || x == width - 1) {
-                    queue!(stdout, cursor::MoveTo(x, y), style::PrintStyledContent("*".magenta()))?;
+        // Run as many fixed steps as real time calls for; the session
+        // decides whether a step moves the world or only the countdown
+        let tick = game_loop.tick();
+        for _ in 0..tick.steps {
+            sim_clock.advance(game_loop.get_step());
+            animation.update(&mut game_session);
+            if let Some(replay) = &mut recording {
+                replay.tick();
+            }
+            if game_session.get_status() == GameStatus::Title {
+                demo.step(autopilot.decide(demo.get_animation(), demo.get_session()));
+                if demo.is_done() {
+                    demo.reset(demo.get_seed().wrapping_add(1));
                 }
             }
         }
 
-        // Update animation with game session
-        animation.update(&mut game_session);
-
-        // Draw score and level
-        queue!(
-            stdout,
-            cursor::MoveTo(2, 1),
-            style::PrintStyledContent(format!("Level: {}", game_session.get_level()).green()),
-            cursor::MoveTo(2, 2),
-            style::PrintStyledContent(format!("Score: {}", game_session.get_score()).green()),
-        )?;
-
-        // Draw rectangles
-        for rect in animation.get_rectangles() {
-            for (x, y, char, color) in rect.draw() {
-                queue!(
-                    stdout,
-                    cursor::MoveTo(x, y),
-                    style::PrintStyledContent(char.with(color))
-                )?;
+        // Offer a finished run to the high-score table once
+        if game_session.get_status() == GameStatus::GameOver && !recorded {
+            recorded = true;
+            if let Err(err) = save_replay(recording.as_ref(), record_path.as_deref()) {
+                notice = Some(err.to_string());
+            }
+            if scores.qualifies(game_session.get_score()) {
+                panel = Some(Panel::NameEntry(NameEntry::new()));
             }
         }
 
-        // Draw bird
-        for (x, y, char, color) in animation.get_bird().draw() {
-            queue!(
-                stdout,
-                cursor::MoveTo(x, y),
-                style::PrintStyledContent(char.with(color))
-            )?;
+        if !tick.render {
+            std::thread::sleep(game_loop.time_until_next());
+            continue;
         }
 
-        // Draw game over message
-        if animation.is_game_over() {
-            let game_over_text = "GAME OVER! Press ESC to quit";
-            let restart_text = "Press R to restart";
-            let text_x = (width - game_over_text.len() as u16) / 2;
-            let text_y = height / 2;
-            
-            queue!(
-                stdout,
-                cursor::MoveTo(text_x, text_y),
-                style::PrintStyledContent(game_over_text.red()),
-                cursor::MoveTo((width - restart_text.len() as u16) / 2, text_y + 2),
-                style::PrintStyledContent(restart_text.green())
-            )?;
+        match (&panel, game_session.get_status()) {
+            (Some(Panel::Leaderboard { highlight }), _) => {
+                draw_leaderboard(&mut renderer, &scores, *highlight, &settings.colors)?;
+                draw_leaderboard_hint(&mut renderer, &settings.colors)?;
+            }
+            (_, GameStatus::Title) => {
+                draw_scene_at(&mut renderer, demo.get_animation(), demo.get_session(), &settings.colors, tick.alpha)?;
+                draw_title(&mut renderer, &settings.colors)?;
+            }
+            (panel, status) => {
+                draw_scene_at(&mut renderer, &animation, &game_session, &settings.colors, tick.alpha)?;
+                match status {
+                    GameStatus::Countdown => {
+                        draw_countdown(&mut renderer, game_session.get_countdown(), &settings.colors)?;
+                    }
+                    GameStatus::Paused => draw_paused(&mut renderer, &settings.colors)?,
+                    _ => {}
+                }
+                if let Some(Panel::NameEntry(entry)) = panel {
+                    draw_name_prompt(&mut renderer, entry.get_name(), &settings.colors)?;
+                }
+            }
         }
 
-        let game_level = game_session.get_level();
-        if game_level > current_level {
-            current_level = game_level;
-            update_interval = (update_interval as f32 * 0.9) as u64; // Increase speed by reducing interval
+        // Keep the previous settings but tell the designer why the reload failed
+        if let Some(text) = &notice {
+            renderer.draw_text(2, 3, text, settings.colors.game_over)?;
         }
 
+        if let Some(err) = controller.get_error() {
+            let text = format!("Controller ignored: {}", err);
+            renderer.draw_text(2, 4, &text, settings.colors.game_over)?;
+        } else if controller.was_connected() && !controller.is_connected() {
+            renderer.draw_text(2, 4, "Controller disconnected, retrying...", settings.colors.game_over)?;
+        }
 
-        stdout.flush()?;
-        std::thread::sleep(Duration::from_millis(update_interval));
+        if settings.show_fps {
+            let stats = game_loop.get_stats();
+            let text = format!("{:.0} fps {:.1} ms", stats.fps, stats.frame_time.as_secs_f32() * 1000.0);
+            let x = screen_size.0.saturating_sub(text.len() as u16 + 2);
+            renderer.draw_text(x, 1, &text, settings.colors.hud)?;
+        }
+
+        renderer.present()?;
+        std::thread::sleep(game_loop.time_until_next());
     }
 
-    game_session.cleanup_terminal()?;
+    renderer.backend_mut().cleanup()?;
+    save_replay(recording.as_ref(), record_path.as_deref())?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Shown on top of whatever state the session is in
+enum Panel {
+    /// Asking for a name after a run made the high-score table
+    NameEntry(NameEntry),
+    /// The high-score table; `highlight` is the rank just added
+    Leaderboard { highlight: Option<usize> },
+}
+
+/// Adds an input to the session's replay, once a run has started
+fn record(recording: &mut Option<Replay>, input: ReplayInput) {
+    if let Some(replay) = recording {
+        replay.record(input);
+    }
+}
+
+/// Writes the session's replay, if a run has started and there is a place for it
+fn save_replay(replay: Option<&Replay>, path: Option<&Path>) -> Result<(), ReplayError> {
+    match (replay, path) {
+        (Some(replay), Some(path)) => replay.save(path),
+        _ => Ok(()),
+    }
+}
+
+/// Creates the title screen's demo run with the sprites from `settings`
+fn new_demo(screen_size: (u16, u16), settings: &Settings, step: Duration, seed: u64) -> Result<Simulation, ConfigError> {
+    let mut demo = Simulation::try_with_step(screen_size, settings.game, seed, step)?;
+    let animation = demo.animation_mut();
+    if let Some(sprite) = &settings.bird_sprite {
+        animation.set_bird_sprite(sprite.clone());
+    }
+    animation.set_obstacle_texture(settings.obstacle_texture.clone());
+    Ok(demo)
+}
+
+/// Creates an animation with the physics and sprites from `settings`
+fn new_animation(
+    screen_size: (u16, u16),
+    settings: &Settings,
+    clock: ManualClock,
+) -> Result<Animation<ManualClock>, ConfigError> {
+    let mut animation = Animation::try_with_clock(screen_size, settings.game, clock)?;
+    if let Some(sprite) = &settings.bird_sprite {
+        animation.set_bird_sprite(sprite.clone());
+    }
+    animation.set_obstacle_texture(settings.obstacle_texture.clone());
+    Ok(animation)
+}
@@ -0,0 +1,100 @@
+use std::io;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use game_lib::highscore::MAX_NAME_LENGTH;
+use game_lib::input::Action;
+
+/// What the keys pressed so far did to the name being typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// Still typing
+    Editing,
+    /// Enter was pressed with this name
+    Done(String),
+    /// Esc was pressed; the score is not recorded
+    Cancelled,
+}
+
+/// Collects a player's name for the high-score table
+///
+/// Typing adds characters up to the table's name length, Backspace removes
+/// the last one, Enter accepts and Esc gives up.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NameEntry {
+    name: String,
+}
+
+impl NameEntry {
+    /// Starts with an empty name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the name typed so far
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Applies a single key press
+    pub fn handle_key(&mut self, code: KeyCode) -> Entry {
+        match code {
+            KeyCode::Char(ch) if !ch.is_control() && self.name.chars().count() < MAX_NAME_LENGTH => {
+                self.name.push(ch);
+            }
+            KeyCode::Backspace => {
+                self.name.pop();
+            }
+            KeyCode::Enter => return Entry::Done(self.name.clone()),
+            KeyCode::Esc => return Entry::Cancelled,
+            _ => {}
+        }
+        Entry::Editing
+    }
+
+    /// Applies the key presses waiting in the terminal
+    ///
+    /// Stops at Enter or Esc, leaving later keys for the next screen.
+    /// Resizes of the terminal are appended to `actions`, as the keyboard
+    /// source would.
+    pub fn poll(&mut self, actions: &mut Vec<Action>) -> io::Result<Entry> {
+        while event::poll(Duration::ZERO)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    let entry = self.handle_key(key.code);
+                    if entry != Entry::Editing {
+                        return Ok(entry);
+                    }
+                }
+                Event::Resize(width, height) => actions.push(Action::Resize(width, height)),
+                _ => {}
+            }
+        }
+        Ok(Entry::Editing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typing_and_editing() {
+        let mut entry = NameEntry::new();
+        for code in [KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Backspace, KeyCode::Char('n'), KeyCode::Left] {
+            assert_eq!(entry.handle_key(code), Entry::Editing);
+        }
+        assert_eq!(entry.get_name(), "an");
+        assert_eq!(entry.handle_key(KeyCode::Enter), Entry::Done("an".to_string()));
+        assert_eq!(entry.handle_key(KeyCode::Esc), Entry::Cancelled);
+    }
+
+    #[test]
+    fn test_name_length_is_capped() {
+        let mut entry = NameEntry::new();
+        for _ in 0..MAX_NAME_LENGTH + 5 {
+            entry.handle_key(KeyCode::Char('z'));
+        }
+        assert_eq!(entry.get_name().len(), MAX_NAME_LENGTH);
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Result;
+use std::time::Duration;
+
+use game_lib::render::Renderer;
+use game_lib::scene::Theme;
+
+/// Draws the title and the keys that lead away from it over the demo run
+pub fn draw_title<R: Renderer + ?Sized>(renderer: &mut R, theme: &Theme) -> Result<()> {
+    let (width, height) = renderer.size()?;
+
+    let centered = |text: &str| width.saturating_sub(text.len() as u16) / 2;
+    let title = "PLATFORM GAME";
+    let text_y = height / 2;
+    renderer.draw_text(centered(title), text_y.saturating_sub(3), title, theme.hud)?;
+    for (row, line) in ["Press Space to play", "Press L for high scores", "Press ESC to quit"]
+        .into_iter()
+        .enumerate()
+    {
+        renderer.draw_text(centered(line), text_y + row as u16, line, theme.message)?;
+    }
+    Ok(())
+}
+
+/// Draws the name prompt below the game-over text
+pub fn draw_name_prompt<R: Renderer + ?Sized>(renderer: &mut R, name: &str, theme: &Theme) -> Result<()> {
+    let (width, height) = renderer.size()?;
+    let centered = |text: &str| width.saturating_sub(text.chars().count() as u16) / 2;
+    let text_y = height / 2 + 6;
+
+    let title = "New high score! Type your name";
+    let field = format!("> {}_", name);
+    let hint = "Enter to save, ESC to skip";
+    renderer.draw_text(centered(title), text_y, title, theme.game_over)?;
+    renderer.draw_text(centered(&field), text_y + 1, &field, theme.hud)?;
+    renderer.draw_text(centered(hint), text_y + 2, hint, theme.message)?;
+    Ok(())
+}
+
+/// Draws the way back from the leaderboard on the last row inside the border
+pub fn draw_leaderboard_hint<R: Renderer + ?Sized>(renderer: &mut R, theme: &Theme) -> Result<()> {
+    let (width, height) = renderer.size()?;
+    let hint = "Press L or ESC to go back";
+    renderer.draw_text(width.saturating_sub(hint.len() as u16) / 2, height.saturating_sub(3), hint, theme.message)
+}
+
+/// Draws the seconds left before the run starts, above the bird
+pub fn draw_countdown<R: Renderer + ?Sized>(renderer: &mut R, remaining: Duration, theme: &Theme) -> Result<()> {
+    let (width, height) = renderer.size()?;
+    // Round up so the last second shows as 1 rather than 0
+    let text = format!("Get ready... {}", (remaining.as_millis() + 999) / 1000);
+    let x = width.saturating_sub(text.len() as u16) / 2;
+    renderer.draw_text(x, (height / 2).saturating_sub(4), &text, theme.hud)
+}
+
+/// Draws the pause text over the frozen run
+pub fn draw_paused<R: Renderer + ?Sized>(renderer: &mut R, theme: &Theme) -> Result<()> {
+    let (width, height) = renderer.size()?;
+    let centered = |text: &str| width.saturating_sub(text.len() as u16) / 2;
+    let text_y = (height / 2).saturating_sub(4);
+
+    let title = "PAUSED";
+    let hint = "P to resume, R to restart, ESC for the title";
+    renderer.draw_text(centered(title), text_y, title, theme.hud)?;
+    renderer.draw_text(centered(hint), text_y + 1, hint, theme.message)?;
+    Ok(())
+}
+
+/// Draws the replay's progress and controls on the last row inside the border
+pub fn draw_replay_status<R: Renderer + ?Sized>(
+    renderer: &mut R,
+    tick: u64,
+    ticks: u64,
+    state: &str,
+    theme: &Theme,
+) -> Result<()> {
+    let (_, height) = renderer.size()?;
+    let text = format!(
+        "REPLAY {}/{} {}  P pause  N step  F fast  R rewind  ESC quit",
+        tick, ticks, state
+    );
+    renderer.draw_text(2, height.saturating_sub(2), &text, theme.hud)
+}
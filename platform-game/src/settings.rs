@@ -0,0 +1,492 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use game_lib::asset::{load_sprite, AssetError};
+use game_lib::config::{ConfigError, GameConfig};
+use game_lib::difficulty::{Difficulty, Preset};
+use game_lib::render::Color;
+use game_lib::scene::Theme;
+use game_lib::sprite::{AnimatedSprite, Sprite};
+use serde::Deserialize;
+
+/// Length of a simulation step in ms
+pub const FRAME_INTERVAL: u64 = 40;
+
+/// Delay between drawn frames in ms
+pub const RENDER_INTERVAL: u64 = 20;
+
+/// Everything a designer can tune without recompiling
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub game: GameConfig,
+    pub frame_interval: u64,
+    pub render_interval: u64,
+    pub show_fps: bool,
+    pub colors: Theme,
+    /// Custom bird skin; the built-in bird when `None`
+    pub bird_sprite: Option<AnimatedSprite>,
+    /// Texture tiled over obstacles; plain blocks when `None`
+    pub obstacle_texture: Option<Sprite>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            frame_interval: FRAME_INTERVAL,
+            render_interval: RENDER_INTERVAL,
+            show_fps: false,
+            colors: Theme::default(),
+            bird_sprite: None,
+            obstacle_texture: None,
+        }
+    }
+}
+
+/// On-disk layout of a settings file
+///
+/// Every key is optional; missing keys keep their default value.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SettingsFile {
+    gravity: Option<f32>,
+    jump_velocity: Option<f32>,
+    update_interval: Option<f32>,
+    max_velocity: Option<f32>,
+    bird_x: Option<u16>,
+    // Level 1 values from before difficulty curves; see `LevelFile::legacy`
+    rect_interval: Option<u64>,
+    max_rect_width: Option<u16>,
+    scroll_speed: Option<f32>,
+    pipe_gap: Option<u16>,
+    speed_up: Option<f32>,
+    frame_interval: Option<u64>,
+    render_interval: Option<u64>,
+    show_fps: Option<bool>,
+    bird_sprite: Option<PathBuf>,
+    obstacle_texture: Option<PathBuf>,
+    difficulty: DifficultyFile,
+    colors: ColorsFile,
+    score: ScoreFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DifficultyFile {
+    preset: Option<String>,
+    rate: Option<f32>,
+    start: LevelFile,
+    limit: LevelFile,
+}
+
+/// Overrides for one end of the difficulty curve
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LevelFile {
+    scroll_speed: Option<f32>,
+    rect_interval: Option<u64>,
+    min_width: Option<u16>,
+    max_width: Option<u16>,
+    min_height: Option<u16>,
+    max_height: Option<u16>,
+    min_gap: Option<u16>,
+    max_gap: Option<u16>,
+}
+
+impl LevelFile {
+    /// Reads the top-level obstacle keys of older files, which set level 1
+    fn legacy(file: &SettingsFile) -> Self {
+        Self {
+            scroll_speed: file.scroll_speed,
+            rect_interval: file.rect_interval,
+            max_width: file.max_rect_width,
+            min_gap: file.pipe_gap,
+            max_gap: file.pipe_gap,
+            ..Self::default()
+        }
+    }
+
+    fn apply(&self, level: &mut Difficulty) {
+        level.scroll_speed = self.scroll_speed.unwrap_or(level.scroll_speed);
+        level.rect_interval = self.rect_interval.unwrap_or(level.rect_interval);
+        level.min_width = self.min_width.unwrap_or(level.min_width);
+        level.max_width = self.max_width.unwrap_or(level.max_width);
+        level.min_height = self.min_height.unwrap_or(level.min_height);
+        level.max_height = self.max_height.unwrap_or(level.max_height);
+        level.min_gap = self.min_gap.unwrap_or(level.min_gap);
+        level.max_gap = self.max_gap.unwrap_or(level.max_gap);
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ColorsFile {
+    border: Option<String>,
+    hud: Option<String>,
+    game_over: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ScoreFile {
+    pass_points: Option<u32>,
+    close_call_points: Option<u32>,
+    close_call_distance: Option<u16>,
+    max_combo: Option<u32>,
+}
+
+impl Settings {
+    /// Reads settings from a TOML file
+    ///
+    /// Sprite paths in the file are relative to the file itself.
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        let text = fs::read_to_string(path).map_err(SettingsError::Io)?;
+        Self::from_toml(&text, path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Parses settings from TOML text, resolving sprite paths from `base`
+    ///
+    /// The obstacle keys of files from before difficulty curves still load:
+    /// `rect_interval`, `max_rect_width`, `scroll_speed` and `pipe_gap` set
+    /// the start of the curve, under anything in `[difficulty.start]`.
+    /// `speed_up` has no counterpart and is rejected.
+    pub fn from_toml(text: &str, base: &Path) -> Result<Self, SettingsError> {
+        let file: SettingsFile = toml::from_str(text).map_err(SettingsError::Parse)?;
+        if file.speed_up.is_some() {
+            return Err(SettingsError::Replaced("speed_up", "rate in [difficulty]"));
+        }
+        let mut settings = Self::default();
+
+        let game = &mut settings.game;
+        game.gravity = file.gravity.unwrap_or(game.gravity);
+        game.jump_velocity = file.jump_velocity.unwrap_or(game.jump_velocity);
+        game.update_interval = file.update_interval.unwrap_or(game.update_interval);
+        game.max_velocity = file.max_velocity.unwrap_or(game.max_velocity);
+        game.bird_x = file.bird_x.unwrap_or(game.bird_x);
+        let difficulty = &file.difficulty;
+        if let Some(name) = &difficulty.preset {
+            let preset: Preset = name.parse().map_err(|_| SettingsError::Difficulty(name.clone()))?;
+            game.difficulty = preset.curve();
+        }
+        let curve = &mut game.difficulty;
+        LevelFile::legacy(&file).apply(&mut curve.start);
+        difficulty.start.apply(&mut curve.start);
+        difficulty.limit.apply(&mut curve.limit);
+        curve.rate = difficulty.rate.unwrap_or(curve.rate);
+        let score = &mut game.score;
+        score.pass_points = file.score.pass_points.unwrap_or(score.pass_points);
+        score.close_call_points = file.score.close_call_points.unwrap_or(score.close_call_points);
+        score.close_call_distance = file.score.close_call_distance.unwrap_or(score.close_call_distance);
+        score.max_combo = file.score.max_combo.unwrap_or(score.max_combo);
+        game.validate().map_err(SettingsError::Invalid)?;
+
+        settings.frame_interval = file.frame_interval.unwrap_or(settings.frame_interval);
+        if settings.frame_interval == 0 {
+            return Err(SettingsError::FrameInterval);
+        }
+        settings.render_interval = file.render_interval.unwrap_or(settings.render_interval);
+        if settings.render_interval == 0 {
+            return Err(SettingsError::RenderInterval);
+        }
+        settings.show_fps = file.show_fps.unwrap_or(settings.show_fps);
+
+        let colors = &mut settings.colors;
+        colors.border = parse_color(file.colors.border, colors.border)?;
+        colors.hud = parse_color(file.colors.hud, colors.hud)?;
+        colors.game_over = parse_color(file.colors.game_over, colors.game_over)?;
+        colors.message = parse_color(file.colors.message, colors.message)?;
+
+        if let Some(path) = file.bird_sprite {
+            let sprite = load_asset(&base.join(path))?;
+            settings.game.validate_bird(&sprite).map_err(SettingsError::Invalid)?;
+            settings.bird_sprite = Some(sprite);
+        }
+        if let Some(path) = file.obstacle_texture {
+            // Textures are stills; only the first frame is used
+            let texture = load_asset(&base.join(path))?;
+            settings.obstacle_texture = Some(texture.get_frames()[0].clone());
+        }
+
+        Ok(settings)
+    }
+}
+
+fn load_asset(path: &Path) -> Result<AnimatedSprite, SettingsError> {
+    load_sprite(path).map_err(|err| SettingsError::Asset(path.to_path_buf(), err))
+}
+
+fn parse_color(name: Option<String>, default: Color) -> Result<Color, SettingsError> {
+    match name {
+        Some(name) => name.parse().map_err(|_| SettingsError::Color(name)),
+        None => Ok(default),
+    }
+}
+
+/// Reasons a settings file could not be applied
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(ConfigError),
+    Color(String),
+    Difficulty(String),
+    /// A key from an older version, and what took its place
+    Replaced(&'static str, &'static str),
+    Asset(PathBuf, AssetError),
+    FrameInterval,
+    RenderInterval,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(err) => write!(f, "cannot read config: {}", err),
+            SettingsError::Parse(err) => write!(f, "cannot parse config: {}", err),
+            SettingsError::Invalid(err) => write!(f, "invalid config: {}", err),
+            SettingsError::Color(name) => write!(f, "unknown color '{}'", name),
+            SettingsError::Difficulty(name) => write!(f, "unknown difficulty '{}', expected easy, normal or hard", name),
+            SettingsError::Replaced(key, by) => write!(f, "'{}' is no longer supported, use {}", key, by),
+            SettingsError::Asset(path, err) => write!(f, "cannot load {}: {}", path.display(), err),
+            SettingsError::FrameInterval => f.write_str("frame interval should be positive"),
+            SettingsError::RenderInterval => f.write_str("render interval should be positive"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Watches a settings file for changes
+///
+/// The file's modification time and size are compared on every poll, so
+/// edits are picked up whenever the game asks, e.g. when a run restarts. The
+/// size catches quick edits the filesystem gives the same timestamp.
+#[derive(Debug)]
+pub struct SettingsWatcher {
+    path: PathBuf,
+    modified: Option<(SystemTime, u64)>,
+}
+
+impl SettingsWatcher {
+    /// Starts watching `path` from its current state
+    pub fn new(path: PathBuf) -> Self {
+        let modified = modified_time(&path);
+        Self { path, modified }
+    }
+
+    /// Reloads the file if it changed since the last poll
+    ///
+    /// Returns `None` when the file is unchanged.
+    pub fn poll(&mut self) -> Option<Result<Settings, SettingsError>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Settings::load(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_lib::difficulty::DifficultyCurve;
+
+    fn parse(text: &str) -> Result<Settings, SettingsError> {
+        Settings::from_toml(text, Path::new(""))
+    }
+
+    #[test]
+    fn test_empty_file_uses_defaults() {
+        assert_eq!(parse("").unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_example_file_matches_defaults() {
+        let settings = parse(include_str!("../game.toml")).unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let settings = parse(
+            r#"
+            gravity = 0.5
+            frame_interval = 30
+
+            [difficulty]
+            preset = "hard"
+
+            [colors]
+            border = "blue"
+
+            [score]
+            close_call_points = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.game.gravity, 0.5);
+        assert_eq!(settings.game.difficulty, DifficultyCurve::hard());
+        assert_eq!(settings.game.jump_velocity, GameConfig::default().jump_velocity);
+        assert_eq!(settings.frame_interval, 30);
+        assert_eq!(settings.colors.border, Color::Blue);
+        assert_eq!(settings.colors.hud, Color::Green);
+        assert_eq!(settings.game.score.close_call_points, 5);
+        assert_eq!(settings.game.score.pass_points, 1);
+    }
+
+    #[test]
+    fn test_rejects_bad_values() {
+        assert!(matches!(
+            parse("gravity = -1.0"),
+            Err(SettingsError::Invalid(ConfigError::Gravity))
+        ));
+        assert!(matches!(
+            parse("bird_x = 65535"),
+            Err(SettingsError::Invalid(ConfigError::BirdX))
+        ));
+        assert!(matches!(
+            parse("[difficulty]\npreset = \"brutal\""),
+            Err(SettingsError::Difficulty(_))
+        ));
+        assert!(matches!(
+            parse("render_interval = 0"),
+            Err(SettingsError::RenderInterval)
+        ));
+        assert!(matches!(
+            parse("[score]\nmax_combo = 0"),
+            Err(SettingsError::Invalid(ConfigError::MaxCombo))
+        ));
+        assert!(matches!(
+            parse("[colors]\nhud = \"plaid\""),
+            Err(SettingsError::Color(_))
+        ));
+        assert!(matches!(
+            parse("gravty = 1.0"),
+            Err(SettingsError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_difficulty_curve() {
+        let settings = parse(
+            r#"
+            [difficulty]
+            preset = "easy"
+            rate = 0.5
+
+            [difficulty.limit]
+            scroll_speed = 3.0
+            max_gap = 6
+            "#,
+        )
+        .unwrap();
+
+        let curve = settings.game.difficulty;
+        assert_eq!(curve.start, DifficultyCurve::easy().start);
+        assert_eq!(curve.limit.scroll_speed, 3.0);
+        assert_eq!(curve.limit.max_gap, 6);
+        assert_eq!(curve.limit.min_gap, DifficultyCurve::easy().limit.min_gap);
+        assert_eq!(curve.rate, 0.5);
+
+        assert!(matches!(
+            parse("[difficulty.start]\nmin_gap = 2"),
+            Err(SettingsError::Invalid(ConfigError::PipeGap))
+        ));
+        assert!(matches!(
+            parse("[difficulty.start]\nmax_width = 100"),
+            Err(SettingsError::Invalid(ConfigError::ObstacleWidth))
+        ));
+        assert!(matches!(
+            parse("[difficulty]\nrate = 0.0"),
+            Err(SettingsError::Invalid(ConfigError::DifficultyRate))
+        ));
+    }
+
+    #[test]
+    fn test_old_obstacle_keys_set_level_one() {
+        let settings = parse(
+            r#"
+            rect_interval = 1200
+            max_rect_width = 6
+            scroll_speed = 0.5
+            pipe_gap = 7
+
+            [difficulty.start]
+            scroll_speed = 0.75
+            "#,
+        )
+        .unwrap();
+
+        let start = settings.game.difficulty.start;
+        assert_eq!(start.rect_interval, 1200);
+        assert_eq!(start.max_width, 6);
+        assert_eq!((start.min_gap, start.max_gap), (7, 7));
+        // The new table wins over the old key
+        assert_eq!(start.scroll_speed, 0.75);
+        assert_eq!(settings.game.difficulty.limit, DifficultyCurve::normal().limit);
+
+        let err = parse("speed_up = 0.9").unwrap_err();
+        assert!(matches!(err, SettingsError::Replaced("speed_up", _)));
+        assert_eq!(err.to_string(), "'speed_up' is no longer supported, use rate in [difficulty]");
+    }
+
+    #[test]
+    fn test_sprites_resolve_from_config_dir() {
+        let base = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let settings = Settings::from_toml(
+            r#"
+            bird_sprite = "assets/flappy.txt"
+            obstacle_texture = "assets/bricks.txt"
+            "#,
+            base,
+        )
+        .unwrap();
+
+        assert_eq!(settings.bird_sprite.unwrap().get_frames().len(), 2);
+        assert_eq!(settings.obstacle_texture.unwrap().get(0, 0), Some(('=', Color::Grey)));
+
+        assert!(matches!(
+            Settings::from_toml("bird_x = 38\nbird_sprite = \"assets/flappy.txt\"", base),
+            Err(SettingsError::Invalid(ConfigError::BirdX))
+        ));
+        assert!(matches!(
+            Settings::from_toml(r#"bird_sprite = "assets/missing.txt""#, base),
+            Err(SettingsError::Asset(_, AssetError::Io(_)))
+        ));
+    }
+
+    #[test]
+    fn test_watcher_reports_changes() {
+        let path = std::env::temp_dir().join(format!("platform-game-{}.toml", std::process::id()));
+        fs::write(&path, "gravity = 1.0").unwrap();
+
+        let mut watcher = SettingsWatcher::new(path.clone());
+        assert!(watcher.poll().is_none());
+
+        // Written straight away, so the timestamp may not have moved; the
+        // size has
+        fs::write(&path, "gravity = 2.25").unwrap();
+        let settings = watcher.poll().unwrap().unwrap();
+        assert_eq!(settings.game.gravity, 2.25);
+        assert!(watcher.poll().is_none());
+
+        // Same size, so wait out coarse timestamps before rewriting
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        fs::write(&path, "gravity = 1.75").unwrap();
+        let settings = watcher.poll().unwrap().unwrap();
+        assert_eq!(settings.game.gravity, 1.75);
+        assert!(watcher.poll().is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+use std::error::Error;
+use std::time::Duration;
+
+use game_lib::frame::FrameBuffer;
+use game_lib::game::GameStatus;
+use game_lib::game_loop::GameLoop;
+use game_lib::input::{Action, InputSource};
+use game_lib::render::Renderer;
+use game_lib::replay::Player;
+use game_lib::scene::draw_scene_at;
+use game_lib::terminal::KeyboardInput;
+
+use crate::screens::{draw_countdown, draw_replay_status};
+use crate::settings::Settings;
+
+/// Updates played per step of real time while fast-forwarding
+const FAST_FORWARD: u32 = 4;
+
+/// Plays a replay in the terminal until the viewer quits
+///
+/// The replay runs at its recorded speed. P pauses, N plays one update while
+/// paused, F toggles fast-forward and R starts over; the end of the replay
+/// stays on screen until ESC. Resizing the terminal only changes how much
+/// of the recorded screen is shown.
+pub fn run<R: Renderer>(renderer: &mut FrameBuffer<R>, player: &mut Player, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let mut keyboard = KeyboardInput::new();
+    let mut game_loop = GameLoop::new(
+        player.get_replay().step,
+        Duration::from_millis(settings.render_interval),
+    );
+    let mut actions = Vec::new();
+    let mut paused = false;
+    let mut fast = false;
+
+    loop {
+        keyboard.poll(&mut actions)?;
+        let mut steps = 0;
+        for action in actions.drain(..) {
+            match action {
+                Action::Quit => return Ok(()),
+                Action::Pause => paused = !paused,
+                Action::Step if paused => steps += 1,
+                Action::FastForward => fast = !fast,
+                Action::Restart => player.rewind(),
+                Action::Resize(width, height) => renderer.resize((width, height)),
+                _ => {}
+            }
+        }
+
+        let tick = game_loop.tick();
+        if !paused {
+            steps += tick.steps * if fast { FAST_FORWARD } else { 1 };
+        }
+        for _ in 0..steps {
+            player.step();
+        }
+
+        if !tick.render {
+            std::thread::sleep(game_loop.time_until_next());
+            continue;
+        }
+
+        // A replay that is not playing does not update, so draw it where it stopped
+        let alpha = if paused || player.is_finished() { 1.0 } else { tick.alpha };
+        let session = player.get_session();
+        draw_scene_at(renderer, player.get_animation(), session, &settings.colors, alpha)?;
+        if session.get_status() == GameStatus::Countdown {
+            draw_countdown(renderer, session.get_countdown(), &settings.colors)?;
+        }
+        let state = match (player.is_finished(), paused, fast) {
+            (true, _, _) => "END".to_string(),
+            (_, true, _) => "PAUSED".to_string(),
+            (_, _, true) => format!("x{}", FAST_FORWARD),
+            _ => String::new(),
+        };
+        draw_replay_status(renderer, player.get_tick(), player.get_replay().get_ticks(), &state, &settings.colors)?;
+        renderer.present()?;
+        std::thread::sleep(game_loop.time_until_next());
+    }
+}